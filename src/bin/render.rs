@@ -1,85 +1,209 @@
-use clap::Parser;
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use clap::{CommandFactory, FromArgMatches};
 
 use simple_ray_tracer::{
     Result,
     raytracer::{
+        accumulator::Accumulator,
+        aov,
+        background::{Background, GradientBackground, SolidBackground},
+        bvh::Bvh,
         camera::Camera,
-        color::Color,
-        hitable_list::HitableList,
-        materials::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+        checkpoint::{self, Checkpoint},
+        denoise::Denoiser,
+        hitable::Hitable,
         options::Options,
-        sphere::Sphere,
-        vec3::{Point3, Real, Vec3},
+        output::{self, ImageFormat},
+        scene_file::load_scene,
+        scenes::{self, SCENES},
+        vec3::{Real, seed_rng},
     },
 };
 
-fn main() -> Result<()> {
-    // Parse command line options
-    let options = Options::parse();
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
 
-    // World
-    let mut world = HitableList::new();
-
-    let ground_material = Lambertian::new(Color::new(0.5, 0.5, 0.5));
-    world.add(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material,
-    ));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let random_vec = Vec3::random(0.0..1.0);
-            let choose_mat = random_vec.x;
-            let center = Point3::new(
-                a as Real + 0.9 * random_vec.y,
-                0.2,
-                b as Real + 0.9 * random_vec.z,
-            );
+fn run() -> Result<()> {
+    // Parse command line options
+    let matches = Options::command().get_matches();
+    let mut options = Options::from_arg_matches(&matches)?;
 
-            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
-                if choose_mat < 0.8 {
-                    // diffuse
-                    let albedo = Color::random() * Color::random();
-                    let sphere_material = Lambertian::new(albedo);
-                    world.add(Sphere::new(center, 0.2, sphere_material));
-                } else if choose_mat < 0.95 {
-                    // metal
-                    let albedo = Color::random() / 2.0 + 0.5;
-                    let fuzz = Vec3::random(0.0..0.5).x;
-                    let sphere_material = Metal::new(albedo, fuzz);
-                    world.add(Sphere::new(center, 0.2, sphere_material));
-                } else {
-                    // glass
-                    let sphere_material = Dielectric::new(1.5);
-                    world.add(Sphere::new(center, 0.2, sphere_material));
-                }
-            }
+    if options.list_scenes {
+        for entry in SCENES {
+            println!("{:<20} {}", entry.name, entry.description);
         }
+        return Ok(());
     }
 
-    let material1a = Dielectric::new(1.5);
-    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, material1a));
+    // Fail before rendering if the output format is not supported
+    ImageFormat::from_path(options.render.file_name.as_ref())?
+        .bit_depth(options.render.bit_depth)?;
+    if let Some(path) = &options.render.sample_heatmap {
+        ImageFormat::from_path(path.as_ref())?.bit_depth(options.render.bit_depth)?;
+    }
 
-    let material1b = Dielectric::new(1.0 / 1.5);
-    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 0.8, material1b));
+    // A resumed render continues with the checkpoint's seed
+    let checkpoint = options
+        .render
+        .resume
+        .as_ref()
+        .map(Checkpoint::load)
+        .transpose()?;
+    let seed = match (&checkpoint, options.render.seed) {
+        (Some(checkpoint), Some(seed)) if seed != checkpoint.seed => {
+            return Err(
+                format!("The checkpoint was rendered with seed {}", checkpoint.seed).into(),
+            );
+        }
+        (Some(checkpoint), _) => checkpoint.seed,
+        (None, seed) => seed.unwrap_or_else(rand::random),
+    };
+    options.render.seed = Some(seed);
 
-    let material2 = Lambertian::new(Color::new(0.4, 0.2, 0.1));
-    world.add(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, material2));
+    // Seed the random scene generation as well, so a resumed render
+    // rebuilds the same scene
+    seed_rng(seed);
 
-    let material3 = Metal::new(Color::new(0.7, 0.6, 0.5), 0.0);
-    world.add(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3));
+    // World
+    let scene = match &options.scene {
+        Some(path) => load_scene(path)?,
+        None => {
+            let entry = scenes::find(&options.scene_name)
+                .ok_or_else(|| format!("Unknown scene '{}'", options.scene_name))?;
+            (entry.build)()
+        }
+    };
+    options.render.apply_scene_camera(&scene.camera, &matches)?;
+
+    // Checkpoints only continue renders of the same scene and settings
+    let scene_sources = if scene.files.is_empty() {
+        vec![options.scene_name.clone().into_bytes()]
+    } else {
+        scene
+            .files
+            .iter()
+            .map(|path| fs::read(path).map_err(|err| format!("{}: {}", path.display(), err)))
+            .collect::<std::result::Result<_, _>>()?
+    };
+    let fingerprint = checkpoint::fingerprint(&scene_sources, &options.render);
+    if let Some(checkpoint) = &checkpoint {
+        let size = (checkpoint.samples.width(), checkpoint.samples.height());
+        if size != (options.render.width, options.render.height) {
+            return Err(format!(
+                "The checkpoint is {}x{}, but the image is {}x{}",
+                size.0, size.1, options.render.width, options.render.height
+            )
+            .into());
+        }
+        if checkpoint.fingerprint != fingerprint {
+            return Err(
+                "The checkpoint was rendered from a different scene or with different camera or render options"
+                    .into(),
+            );
+        }
+    }
+
+    // A background given on the command line replaces the scene's
+    let background: Box<dyn Background> = match (options.render.background, scene.background) {
+        (Some(color), _) => Box::new(SolidBackground::new(color)),
+        (None, Some(background)) => background,
+        (None, None) => Box::new(GradientBackground::default()),
+    };
+
+    // Acceleration structure
+    let world = Bvh::new(scene.world);
+    // Fog only changes the light, the feature passes see the bare surfaces
+    let radiance: Box<dyn Hitable + '_> = match scene.fog {
+        Some(fog) => Box::new(fog.around(&world)),
+        None => Box::new(&world),
+    };
 
     // Camera setup
     let camera = Camera::new(options.render);
+    let file_name = &camera.render_options.file_name;
+    let encode_options = camera.render_options.encode_options();
 
     // Time duration
-    let start = std::time::Instant::now();
+    let start = Instant::now();
+
+    let render = &camera.render_options;
+    let checkpoint_path = render.checkpoint_path();
+    let samples = if render.progressive.is_some() || checkpoint_path.is_some() {
+        let mut samples = match checkpoint {
+            Some(checkpoint) => checkpoint.samples,
+            None => Accumulator::new(render.width, render.height),
+        };
+        let seconds = |seconds: Real, name: &str| {
+            Duration::try_from_secs_f64(seconds)
+                .map_err(|_| format!("The {} must be a positive number of seconds", name))
+        };
+        let snapshot_interval = render
+            .snapshot_interval
+            .map(|interval| seconds(interval, "snapshot interval"))
+            .transpose()?;
+        let checkpoint_interval = seconds(render.checkpoint_interval, "checkpoint interval")?;
+
+        let mut last_snapshot = Instant::now();
+        let mut last_checkpoint = Instant::now();
+        camera.render_passes(&radiance, background.as_ref(), &mut samples, |samples| {
+            if render.progressive.is_some()
+                && snapshot_interval.is_none_or(|interval| last_snapshot.elapsed() >= interval)
+            {
+                output::save(&samples.image(), file_name, &encode_options)?;
+                last_snapshot = Instant::now();
+            }
+            if let Some(path) = checkpoint_path
+                && last_checkpoint.elapsed() >= checkpoint_interval
+            {
+                Checkpoint::save(path, camera.seed(), fingerprint, samples)?;
+                last_checkpoint = Instant::now();
+            }
+            Ok(())
+        })?;
 
-    camera.render(&world)?;
+        // The final state lets a later run add more samples
+        if let Some(path) = checkpoint_path {
+            Checkpoint::save(path, camera.seed(), fingerprint, &samples)?;
+        }
+        samples
+    } else {
+        camera.render_samples(&radiance, background.as_ref())
+    };
 
     let duration = start.elapsed();
     println!("Render time: {:?}", duration);
 
+    // The denoiser and the extra passes need the visible surfaces
+    let render = &camera.render_options;
+    let mut image = samples.image();
+    let features =
+        (render.denoise || !render.aovs.is_empty()).then(|| camera.render_features(&world));
+    if let Some(features) = &features
+        && render.denoise
+    {
+        let denoiser = Denoiser::new(render.denoise_radius);
+        image = denoiser.denoise(&image, features, render.thread_count());
+    }
+
+    match &features {
+        Some(features) if !render.aovs.is_empty() => {
+            aov::save(&image, features, &render.aovs, file_name, &encode_options)?
+        }
+        _ => output::save(&image, file_name, &encode_options)?,
+    }
+
+    if let Some(path) = &camera.render_options.sample_heatmap {
+        let heatmap = samples.sample_heatmap(camera.render_options.samples_per_pixel);
+        output::save(&heatmap, path, &encode_options.raw())?;
+    }
+
     Ok(())
 }
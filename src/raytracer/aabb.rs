@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Aabb {
+    pub fn new(min: Point3, max: Point3) -> Self {
+        Aabb { min, max }
+    }
+
+    /// Returns the smallest box containing both points, in any order
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        Aabb::new(a.min(b), b.max(a))
+    }
+
+    /// Returns a box that contains nothing, the identity for `union`
+    pub fn empty() -> Self {
+        Aabb::new(
+            Point3::new(Real::INFINITY, Real::INFINITY, Real::INFINITY),
+            Point3::new(-Real::INFINITY, -Real::INFINITY, -Real::INFINITY),
+        )
+    }
+
+    /// Returns the smallest box containing a disk with a unit `normal`
+    pub fn around_disk(center: Point3, normal: Vec3, radius: Real) -> Self {
+        // How far the rim reaches along each axis
+        let n = normal;
+        let reach = Vec3::new(
+            (1.0 - n.x * n.x).max(0.0).sqrt(),
+            (1.0 - n.y * n.y).max(0.0).sqrt(),
+            (1.0 - n.z * n.z).max(0.0).sqrt(),
+        ) * radius;
+        Aabb::new(center - reach, center + reach)
+    }
+
+    /// Returns a box containing all of space, for unbounded objects
+    pub fn infinite() -> Self {
+        Aabb::new(
+            Point3::new(-Real::INFINITY, -Real::INFINITY, -Real::INFINITY),
+            Point3::new(Real::INFINITY, Real::INFINITY, Real::INFINITY),
+        )
+    }
+
+    /// Returns whether the box reaches infinity along any axis
+    pub fn is_unbounded(&self) -> bool {
+        (0..3).any(|axis| self.min[axis] == -Real::INFINITY || self.max[axis] == Real::INFINITY)
+    }
+
+    pub fn union(self, other: Aabb) -> Self {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Returns the box contained in both boxes, which is empty when they
+    /// do not overlap
+    pub fn overlap(self, other: Aabb) -> Self {
+        Aabb::new(self.min.max(other.min), self.max.min(other.max))
+    }
+
+    pub fn grow(self, p: Point3) -> Self {
+        Aabb::new(self.min.min(p), self.max.max(p))
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn extent(&self) -> Point3 {
+        self.max - self.min
+    }
+
+    /// Returns the index of the axis along which the box is widest
+    pub fn longest_axis(&self) -> usize {
+        let extent = self.extent();
+        if extent.x > extent.y && extent.x > extent.z {
+            0
+        } else if extent.y > extent.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn surface_area(&self) -> Real {
+        let extent = self.extent();
+        if extent.x < 0.0 || extent.y < 0.0 || extent.z < 0.0 {
+            return 0.0;
+        }
+        2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+    }
+
+    /// Slab test, returns whether the ray enters the box within the range
+    pub fn hit(&self, ray: &Ray, range: &Range<Real>) -> bool {
+        let mut t_min = range.start;
+        let mut t_max = range.end;
+        for axis in 0..3 {
+            let inv_d = 1.0 / ray.direction[axis];
+            let mut t0 = (self.min[axis] - ray.origin[axis]) * inv_d;
+            let mut t1 = (self.max[axis] - ray.origin[axis]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            // Written so that NaNs from 0 * inf keep the current bounds.
+            if t0 > t_min {
+                t_min = t0;
+            }
+            if t1 < t_max {
+                t_max = t1;
+            }
+            if t_max < t_min {
+                return false;
+            }
+        }
+        true
+    }
+}
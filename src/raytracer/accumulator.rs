@@ -0,0 +1,127 @@
+use std::slice::ChunksMut;
+
+use crate::raytracer::{color::Color, framebuffer::Framebuffer, vec3::Real};
+
+/// Running statistics of the samples taken for one pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelAccumulator {
+    pub(crate) sum: Color,
+    pub(crate) samples: usize,
+    // Welford's online mean and sum of squared deviations of the luminance
+    pub(crate) mean_luminance: Real,
+    pub(crate) m2: Real,
+}
+
+impl Default for PixelAccumulator {
+    fn default() -> Self {
+        PixelAccumulator {
+            sum: Color::black(),
+            samples: 0,
+            mean_luminance: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl PixelAccumulator {
+    pub fn add(&mut self, color: Color) {
+        self.sum += color;
+        self.samples += 1;
+
+        let luminance = color.luminance();
+        let delta = luminance - self.mean_luminance;
+        self.mean_luminance += delta / self.samples as Real;
+        self.m2 += delta * (luminance - self.mean_luminance);
+    }
+
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Returns the average of all samples, black if there are none.
+    pub fn mean(&self) -> Color {
+        if self.samples == 0 {
+            Color::black()
+        } else {
+            self.sum / self.samples as Real
+        }
+    }
+
+    /// Returns the standard error of the mean luminance relative to the mean
+    /// itself, an estimate of how noisy the pixel still is.
+    pub fn relative_error(&self) -> Real {
+        if self.samples < 2 {
+            return Real::INFINITY;
+        }
+        let n = self.samples as Real;
+        let variance = self.m2 / (n - 1.0);
+        let standard_error = (variance / n).sqrt();
+        // Keep very dark pixels from requiring an unreasonable sample count.
+        standard_error / self.mean_luminance.max(0.01)
+    }
+}
+
+/// Per-pixel sample statistics of a whole image.
+#[derive(Debug, Clone)]
+pub struct Accumulator {
+    width: usize,
+    height: usize,
+    pixels: Vec<PixelAccumulator>,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize) -> Self {
+        Accumulator {
+            width,
+            height,
+            pixels: vec![PixelAccumulator::default(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[PixelAccumulator] {
+        &self.pixels
+    }
+
+    /// Returns an iterator over mutable scanlines, top to bottom
+    pub fn rows_mut(&mut self) -> ChunksMut<'_, PixelAccumulator> {
+        self.pixels.chunks_mut(self.width)
+    }
+
+    /// Returns the image made of every pixel's mean
+    pub fn image(&self) -> Framebuffer {
+        let mut image = Framebuffer::new(self.width, self.height);
+        for (pixel, accumulator) in image.pixels_mut().iter_mut().zip(&self.pixels) {
+            *pixel = accumulator.mean();
+        }
+        image
+    }
+
+    /// Returns a false color image of the samples taken per pixel, from dark
+    /// blue for the fewest to red for `max_samples`.
+    pub fn sample_heatmap(&self, max_samples: usize) -> Framebuffer {
+        let ramp = [
+            Color::new(0.0, 0.0, 0.3),
+            Color::new(0.0, 0.5, 1.0),
+            Color::new(0.0, 1.0, 0.0),
+            Color::new(1.0, 1.0, 0.0),
+            Color::new(1.0, 0.0, 0.0),
+        ];
+
+        let mut image = Framebuffer::new(self.width, self.height);
+        for (pixel, accumulator) in image.pixels_mut().iter_mut().zip(&self.pixels) {
+            let t = accumulator.samples() as Real / max_samples.max(1) as Real;
+            let position = t.clamp(0.0, 1.0) * (ramp.len() - 1) as Real;
+            let index = (position as usize).min(ramp.len() - 2);
+            *pixel = ramp[index].lerp(ramp[index + 1], position - index as Real);
+        }
+        image
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    slice::ChunksMut,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    Result,
+    raytracer::{
+        color::Color,
+        framebuffer::Framebuffer,
+        output::{
+            self, EncodeOptions, ImageFormat,
+            exr::{ExrChannel, ExrEncoder},
+        },
+        vec3::{Point3, Real, Vec3},
+    },
+};
+
+/// Arbitrary output variables, extra images describing the first surface
+/// seen through every pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Aov {
+    /// Distance from the camera along the view direction
+    Depth,
+    /// World space surface normal
+    Normal,
+    /// Surface color of the material
+    Albedo,
+    /// World space position
+    Position,
+    /// Position of the object in the scene, starting at 1
+    ObjectId,
+}
+
+impl Aov {
+    pub fn name(self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::Position => "position",
+            Aov::ObjectId => "object_id",
+        }
+    }
+
+    /// Returns the channel names of the pass in an EXR layer
+    fn channel_names(self) -> &'static [&'static str] {
+        match self {
+            Aov::Depth => &["Z"],
+            Aov::Normal | Aov::Position => &["X", "Y", "Z"],
+            Aov::Albedo => &["R", "G", "B"],
+            Aov::ObjectId => &["id"],
+        }
+    }
+
+    /// Returns the raw value of one channel of the pass
+    fn channel(self, features: &Features, channel: usize) -> Real {
+        match self {
+            Aov::Depth => features.depth,
+            Aov::Normal => features.normal[channel],
+            Aov::Albedo => features.albedo.0[channel],
+            Aov::Position => features.position[channel],
+            Aov::ObjectId => features.object_id as Real,
+        }
+    }
+
+    /// Returns the pass as an image. Depth, normals and positions keep their
+    /// raw values and are best saved in a float format, object IDs become a
+    /// distinct color per object.
+    pub fn image(self, features: &FeatureBuffer) -> Framebuffer {
+        let mut image = Framebuffer::new(features.width(), features.height());
+        for (pixel, features) in image.pixels_mut().iter_mut().zip(features.pixels()) {
+            *pixel = match self {
+                Aov::Depth => Color::new(features.depth, features.depth, features.depth),
+                Aov::Normal => features.normal.to_color(),
+                Aov::Albedo => features.albedo,
+                Aov::Position => features.position.to_color(),
+                Aov::ObjectId => id_color(features.object_id),
+            };
+        }
+        image
+    }
+}
+
+/// Returns a color that tells neighbouring IDs apart, black for no object.
+fn id_color(id: usize) -> Color {
+    if id == 0 {
+        return Color::black();
+    }
+    // Spread consecutive IDs around the hue circle with the golden ratio.
+    let hue = (id as Real * 0.618_033_988_749_895).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    match hue as usize {
+        0 => Color::new(1.0, x, 0.0),
+        1 => Color::new(x, 1.0, 0.0),
+        2 => Color::new(0.0, 1.0, x),
+        3 => Color::new(0.0, x, 1.0),
+        4 => Color::new(x, 0.0, 1.0),
+        _ => Color::new(1.0, 0.0, x),
+    }
+}
+
+/// The first surface seen through a pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Features {
+    pub depth: Real,
+    pub normal: Vec3,
+    pub albedo: Color,
+    pub position: Point3,
+    pub object_id: usize,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features {
+            depth: 0.0,
+            normal: Vec3::zero(),
+            albedo: Color::black(),
+            position: Point3::zero(),
+            object_id: 0,
+        }
+    }
+}
+
+/// Features of every pixel of an image
+#[derive(Debug, Clone)]
+pub struct FeatureBuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Features>,
+}
+
+impl FeatureBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        FeatureBuffer {
+            width,
+            height,
+            pixels: vec![Features::default(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Features] {
+        &self.pixels
+    }
+
+    /// Returns an iterator over mutable scanlines, top to bottom
+    pub fn rows_mut(&mut self) -> ChunksMut<'_, Features> {
+        self.pixels.chunks_mut(self.width)
+    }
+}
+
+/// Returns the file a pass is saved to next to the image, e.g.
+/// `image.depth.exr` for `image.exr`.
+pub fn pass_path(path: &Path, aov: Aov) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut file_name = format!("{}.{}", stem, aov.name());
+    if let Some(extension) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+    path.with_file_name(file_name)
+}
+
+/// Saves the image together with the passes. EXR files get the passes as
+/// extra layers, other formats get a file per pass next to the image.
+pub fn save(
+    image: &Framebuffer,
+    features: &FeatureBuffer,
+    aovs: &[Aov],
+    path: impl AsRef<Path>,
+    options: &EncodeOptions,
+) -> Result<()> {
+    let path = path.as_ref();
+    let format = ImageFormat::from_path(path)?;
+    if format != ImageFormat::Exr {
+        output::save(image, path, options)?;
+        for &aov in aovs {
+            output::save(&aov.image(features), pass_path(path, aov), &options.raw())?;
+        }
+        return Ok(());
+    }
+
+    let pixels = image.pixels();
+    let mut channels = vec![
+        ExrChannel::new("R", |i| pixels[i].0.x as f32),
+        ExrChannel::new("G", |i| pixels[i].0.y as f32),
+        ExrChannel::new("B", |i| pixels[i].0.z as f32),
+    ];
+    let features = features.pixels();
+    for &aov in aovs {
+        for (channel, name) in aov.channel_names().iter().enumerate() {
+            channels.push(ExrChannel::new(
+                format!("{}.{}", aov.name(), name),
+                move |i| aov.channel(&features[i], channel) as f32,
+            ));
+        }
+    }
+
+    let encoder = ExrEncoder {
+        bit_depth: format.bit_depth(options.bit_depth)?,
+    };
+    let mut writer = BufWriter::new(File::create(path)?);
+    encoder.encode_channels(image.width(), image.height(), &mut channels, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
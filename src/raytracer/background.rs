@@ -0,0 +1,148 @@
+use std::f64::consts::PI;
+
+use crate::raytracer::{
+    color::Color,
+    framebuffer::Framebuffer,
+    ray::Ray,
+    vec3::{Real, Vec3},
+};
+
+/// Light arriving from infinitely far away, seen by rays that hit nothing.
+pub trait Background: Send + Sync {
+    /// Returns the radiance arriving along the ray.
+    fn color(&self, ray: &Ray) -> Color;
+}
+
+/// The same color in every direction, black for closed interiors.
+pub struct SolidBackground {
+    color: Color,
+}
+
+impl SolidBackground {
+    pub fn new(color: Color) -> Self {
+        SolidBackground { color }
+    }
+}
+
+impl Background for SolidBackground {
+    fn color(&self, _ray: &Ray) -> Color {
+        self.color
+    }
+}
+
+/// Vertical blend from `bottom` straight down to `top` straight up.
+pub struct GradientBackground {
+    bottom: Color,
+    top: Color,
+}
+
+impl GradientBackground {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        GradientBackground { bottom, top }
+    }
+}
+
+impl Default for GradientBackground {
+    /// The white to blue sky from the book
+    fn default() -> Self {
+        GradientBackground::new(Color::new(1.0, 1.0, 1.0), Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Background for GradientBackground {
+    fn color(&self, ray: &Ray) -> Color {
+        let unit_direction = ray.direction.normalize();
+        let t = 0.5 * (unit_direction.y + 1.0);
+        self.bottom.lerp(self.top, t)
+    }
+}
+
+/// Environment map in an equirectangular (latitude-longitude) image. The
+/// center of the image looks down the negative z axis.
+pub struct EnvironmentMap {
+    image: Framebuffer,
+    intensity: Real,
+}
+
+impl EnvironmentMap {
+    pub fn new(image: Framebuffer, intensity: Real) -> Self {
+        EnvironmentMap { image, intensity }
+    }
+
+    /// Bilinearly filtered lookup, wrapping around horizontally.
+    fn sample(&self, u: Real, v: Real) -> Color {
+        let width = self.image.width();
+        let height = self.image.height();
+        let x = u * width as Real - 0.5;
+        let y = (v * height as Real - 0.5).clamp(0.0, (height - 1) as Real);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+
+        let column = |x: Real| (x as isize).rem_euclid(width as isize) as usize;
+        let (x0, x1) = (column(x0), column(x0 + 1.0));
+        let (y0, y1) = (y0 as usize, (y0 as usize + 1).min(height - 1));
+
+        let top = self.image.get(x0, y0).lerp(self.image.get(x1, y0), tx);
+        let bottom = self.image.get(x0, y1).lerp(self.image.get(x1, y1), tx);
+        top.lerp(bottom, ty)
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn color(&self, ray: &Ray) -> Color {
+        let d = ray.direction.normalize();
+        let u = 0.5 + d.x.atan2(-d.z) / (2.0 * PI);
+        let v = d.y.clamp(-1.0, 1.0).acos() / PI;
+        self.sample(u, v) * self.intensity
+    }
+}
+
+/// Simple analytic daylight sky with a sun disk and a uniform ground below
+/// the horizon.
+pub struct ProceduralSky {
+    sun_direction: Vec3,
+    sun_color: Color,
+    cos_sun_radius: Real,
+    zenith: Color,
+    horizon: Color,
+    ground: Color,
+}
+
+impl ProceduralSky {
+    pub fn new(sun_direction: Vec3) -> Self {
+        ProceduralSky {
+            sun_direction: sun_direction.normalize(),
+            sun_color: Color::new(1.0, 0.95, 0.85) * 500.0,
+            // The sun covers about half a degree of the sky.
+            cos_sun_radius: (0.27 as Real).to_radians().cos(),
+            zenith: Color::new(0.25, 0.45, 0.9),
+            horizon: Color::new(0.85, 0.9, 1.0),
+            ground: Color::new(0.35, 0.3, 0.25),
+        }
+    }
+
+    pub fn with_sun_color(mut self, sun_color: Color) -> Self {
+        self.sun_color = sun_color;
+        self
+    }
+}
+
+impl Background for ProceduralSky {
+    fn color(&self, ray: &Ray) -> Color {
+        let d = ray.direction.normalize();
+        if d.y < 0.0 {
+            // Fade into the ground just below the horizon to hide the seam.
+            return self.horizon.lerp(self.ground, (-d.y * 10.0).min(1.0));
+        }
+
+        // The sky gets bluer away from the horizon and brighter near the sun.
+        let sky = self.horizon.lerp(self.zenith, d.y.sqrt());
+        let cos_sun = d.dot(self.sun_direction);
+        let glow = self.sun_color * (0.002 * cos_sun.max(0.0).powi(64));
+        if cos_sun > self.cos_sun_radius {
+            sky + glow + self.sun_color
+        } else {
+            sky + glow
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    hitable_list::HitableList,
+    ray::Ray,
+    vec3::{Point3, Real},
+};
+
+/// Leaves stop splitting once they hold at most this many objects.
+const MAX_LEAF_SIZE: usize = 4;
+/// Number of buckets used to evaluate the surface area heuristic.
+const SAH_BINS: usize = 12;
+
+enum Node {
+    Leaf {
+        bbox: Aabb,
+        first: usize,
+        count: usize,
+    },
+    // The left child always directly follows its parent in the node array.
+    Interior {
+        bbox: Aabb,
+        right: usize,
+        axis: usize,
+    },
+}
+
+impl Node {
+    fn bbox(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bbox, .. } | Node::Interior { bbox, .. } => bbox,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BuildItem {
+    bbox: Aabb,
+    centroid: Point3,
+    index: usize,
+}
+
+/// Bounding volume hierarchy over a set of objects, built with the binned
+/// surface area heuristic. Unbounded objects such as infinite planes are kept
+/// out of the tree and tested on every ray.
+pub struct Bvh<'a> {
+    nodes: Vec<Node>,
+    hitables: Vec<Box<dyn Hitable + 'a>>,
+    /// Position of every object in the original list
+    indices: Vec<usize>,
+    /// Objects from here on are unbounded and not part of the tree
+    first_unbounded: usize,
+}
+
+impl<'a> Bvh<'a> {
+    pub fn new(list: HitableList<'a>) -> Self {
+        let hitables = list.into_hitables();
+        let (mut items, unbounded): (Vec<BuildItem>, Vec<BuildItem>) = hitables
+            .iter()
+            .enumerate()
+            .map(|(index, hitable)| {
+                let bbox = hitable.bounding_box();
+                BuildItem {
+                    bbox,
+                    centroid: bbox.centroid(),
+                    index,
+                }
+            })
+            .partition(|item| !item.bbox.is_unbounded());
+
+        let mut nodes = Vec::with_capacity(2 * items.len().max(1));
+        if items.is_empty() {
+            nodes.push(Node::Leaf {
+                bbox: Aabb::empty(),
+                first: 0,
+                count: 0,
+            });
+        } else {
+            Bvh::build(&mut nodes, &mut items, 0);
+        }
+
+        // Reorder the objects so every leaf refers to a contiguous range.
+        let first_unbounded = items.len();
+        items.extend(unbounded);
+        let mut slots: Vec<Option<Box<dyn Hitable + 'a>>> =
+            hitables.into_iter().map(Some).collect();
+        let hitables = items
+            .iter()
+            .map(|item| slots[item.index].take().unwrap())
+            .collect();
+
+        let indices = items.iter().map(|item| item.index).collect();
+
+        Bvh {
+            nodes,
+            hitables,
+            indices,
+            first_unbounded,
+        }
+    }
+
+    /// Recursively builds the subtree for `items`, which start at `offset` in
+    /// the final object order. Returns the index of the subtree root.
+    fn build(nodes: &mut Vec<Node>, items: &mut [BuildItem], offset: usize) -> usize {
+        let bbox = items
+            .iter()
+            .fold(Aabb::empty(), |bbox, item| bbox.union(item.bbox));
+        let node_index = nodes.len();
+        let leaf = Node::Leaf {
+            bbox,
+            first: offset,
+            count: items.len(),
+        };
+
+        if items.len() <= 1 {
+            nodes.push(leaf);
+            return node_index;
+        }
+
+        let centroid_bounds = items
+            .iter()
+            .fold(Aabb::empty(), |bounds, item| bounds.grow(item.centroid));
+        let axis = centroid_bounds.longest_axis();
+        let extent = centroid_bounds.extent()[axis];
+
+        let mid = if extent > 0.0 {
+            match Bvh::sah_split(items, &bbox, &centroid_bounds, axis) {
+                Some(mid) => mid,
+                None if items.len() <= MAX_LEAF_SIZE => {
+                    nodes.push(leaf);
+                    return node_index;
+                }
+                None => Bvh::median_split(items, axis),
+            }
+        } else if items.len() <= MAX_LEAF_SIZE {
+            // All centroids coincide, splitting would not separate anything.
+            nodes.push(leaf);
+            return node_index;
+        } else {
+            items.len() / 2
+        };
+
+        nodes.push(Node::Interior {
+            bbox,
+            right: 0,
+            axis,
+        });
+        let (left_items, right_items) = items.split_at_mut(mid);
+        Bvh::build(nodes, left_items, offset);
+        let right_index = Bvh::build(nodes, right_items, offset + mid);
+        if let Node::Interior { right, .. } = &mut nodes[node_index] {
+            *right = right_index;
+        }
+        node_index
+    }
+
+    /// Partitions the items at the cheapest bucket boundary. Returns `None`
+    /// when keeping the items in a single leaf is cheaper.
+    fn sah_split(
+        items: &mut [BuildItem],
+        bbox: &Aabb,
+        centroid_bounds: &Aabb,
+        axis: usize,
+    ) -> Option<usize> {
+        let min = centroid_bounds.min[axis];
+        let extent = centroid_bounds.extent()[axis];
+        let bin_of = |item: &BuildItem| {
+            let bin = ((item.centroid[axis] - min) / extent * SAH_BINS as Real) as usize;
+            bin.min(SAH_BINS - 1)
+        };
+
+        let mut bin_boxes = [Aabb::empty(); SAH_BINS];
+        let mut bin_counts = [0usize; SAH_BINS];
+        for item in items.iter() {
+            let bin = bin_of(item);
+            bin_boxes[bin] = bin_boxes[bin].union(item.bbox);
+            bin_counts[bin] += 1;
+        }
+
+        // Sweep from the right to get the cost of every right-hand side.
+        let mut right_areas = [0.0; SAH_BINS];
+        let mut right_counts = [0usize; SAH_BINS];
+        let mut right_box = Aabb::empty();
+        let mut right_count = 0;
+        for bin in (1..SAH_BINS).rev() {
+            right_box = right_box.union(bin_boxes[bin]);
+            right_count += bin_counts[bin];
+            right_areas[bin] = right_box.surface_area();
+            right_counts[bin] = right_count;
+        }
+
+        let mut best: Option<(usize, Real)> = None;
+        let mut left_box = Aabb::empty();
+        let mut left_count = 0;
+        for split in 1..SAH_BINS {
+            left_box = left_box.union(bin_boxes[split - 1]);
+            left_count += bin_counts[split - 1];
+            if left_count == 0 || right_counts[split] == 0 {
+                continue;
+            }
+            let cost = left_box.surface_area() * left_count as Real
+                + right_areas[split] * right_counts[split] as Real;
+            if best.is_none_or(|(_, best_cost)| cost < best_cost) {
+                best = Some((split, cost));
+            }
+        }
+
+        let (split, cost) = best?;
+        // Traversing a node costs roughly as much as one intersection test.
+        let split_cost = bbox.surface_area() + cost;
+        let leaf_cost = bbox.surface_area() * items.len() as Real;
+        if items.len() <= MAX_LEAF_SIZE && split_cost >= leaf_cost {
+            return None;
+        }
+
+        let mut mid = 0;
+        for i in 0..items.len() {
+            if bin_of(&items[i]) < split {
+                items.swap(i, mid);
+                mid += 1;
+            }
+        }
+        Some(mid)
+    }
+
+    fn median_split(items: &mut [BuildItem], axis: usize) -> usize {
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+        mid
+    }
+}
+
+impl<'a> Hitable for Bvh<'a> {
+    fn bounding_box(&self) -> Aabb {
+        if self.first_unbounded < self.hitables.len() {
+            return Aabb::infinite();
+        }
+        *self.nodes[0].bbox()
+    }
+
+    fn hit(&self, ray: &Ray, interval: &Range<Real>) -> Option<HitRecord<'_>> {
+        let mut closest_hit: Option<HitRecord> = None;
+        let mut closest_interval = interval.clone();
+
+        for index in self.first_unbounded..self.hitables.len() {
+            if let Some(mut hit_record) = self.hitables[index].hit(ray, &closest_interval) {
+                closest_interval = closest_interval.start..hit_record.t;
+                hit_record.object_id = self.indices[index] + 1;
+                closest_hit = Some(hit_record);
+            }
+        }
+
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !node.bbox().hit(ray, &closest_interval) {
+                continue;
+            }
+            match *node {
+                Node::Leaf { first, count, .. } => {
+                    for index in first..first + count {
+                        if let Some(mut hit_record) =
+                            self.hitables[index].hit(ray, &closest_interval)
+                        {
+                            closest_interval = closest_interval.start..hit_record.t;
+                            hit_record.object_id = self.indices[index] + 1;
+                            closest_hit = Some(hit_record);
+                        }
+                    }
+                }
+                Node::Interior { right, axis, .. } => {
+                    // Visit the nearer child first so hits shrink the interval early.
+                    let left = node_index + 1;
+                    if ray.direction[axis] < 0.0 {
+                        stack.push(left);
+                        stack.push(right);
+                    } else {
+                        stack.push(right);
+                        stack.push(left);
+                    }
+                }
+            }
+        }
+
+        closest_hit
+    }
+}
@@ -1,31 +1,88 @@
-use std::{
-    fs::File,
-    io::{BufWriter, Write},
-};
-
 use indicatif::ProgressBar;
 
 use crate::{
     Result,
     raytracer::{
-        color::Color,
+        accumulator::{Accumulator, PixelAccumulator},
+        aov::{FeatureBuffer, Features},
+        background::Background,
+        framebuffer::Framebuffer,
         hitable::Hitable,
         options::RenderOptions,
+        parallel::for_each_row_parallel,
         ray::Ray,
-        vec3::{Point3, Real, Vec3},
+        vec3::{GOLDEN_GAMMA, Point3, Real, Vec3, mix64, seed_rng},
     },
 };
 
+/// Samples per pixel averaged into the feature buffers
+const FEATURE_SAMPLES: usize = 16;
+
+/// Camera position and orientation, and the pixel grid on the focus plane
+struct Frame {
+    origin: Point3,
+    /// Unit vector pointing backwards from the view direction
+    w: Vec3,
+    pixel00_loc: Point3,
+    pixel_delta_u: Vec3,
+    pixel_delta_v: Vec3,
+    defocus_disk_u: Vec3,
+    defocus_disk_v: Vec3,
+    shutter_open: Real,
+    shutter_close: Real,
+}
+
+impl Frame {
+    /// Returns a random ray through pixel `(i, j)`, starting on the defocus
+    /// disk if `defocus` is set.
+    fn sample_ray(&self, i: usize, j: usize, defocus: bool) -> Ray {
+        // Calculate the pixel sample location.
+        let offset = Vec3::sample_square();
+        let pixel_sample = self.pixel00_loc
+            + (self.pixel_delta_u * (i as Real + offset.x))
+            + (self.pixel_delta_v * (j as Real + offset.y));
+
+        // Apply defocus if enabled
+        let ray_origin = self.origin
+            + if defocus {
+                Camera::defocus_disk_sample(self.defocus_disk_u, self.defocus_disk_v)
+            } else {
+                Vec3::zero()
+            };
+
+        // Spread the rays over the time the shutter is open
+        let time = if self.shutter_close > self.shutter_open {
+            self.shutter_open + Vec3::random_real() * (self.shutter_close - self.shutter_open)
+        } else {
+            self.shutter_open
+        };
+
+        let ray_direction = pixel_sample - ray_origin;
+        Ray::with_time(ray_origin, ray_direction, time)
+    }
+}
+
 pub struct Camera {
     pub render_options: RenderOptions,
+    seed: u64,
 }
 
 impl Camera {
+    /// Creates a camera, picking a random seed when none is given.
     pub fn new(render_options: RenderOptions) -> Self {
-        Camera { render_options }
+        let seed = render_options.seed.unwrap_or_else(rand::random);
+        Camera {
+            render_options,
+            seed,
+        }
+    }
+
+    /// Returns the seed all sample streams are derived from
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
-    fn initilize(&self) -> (Point3, Vec3, Vec3, Vec3, Vec3, Vec3) {
+    fn initilize(&self) -> Frame {
         let origin = self.render_options.lookfrom;
 
         // Calculate the u,v,w unit basis vectors for the camera coordinate frame.
@@ -52,69 +109,188 @@ impl Camera {
         let defocus_disk_u = u * defocus_radius;
         let defocus_disk_v = v * defocus_radius;
 
-        (
+        Frame {
             origin,
+            w,
             pixel00_loc,
             pixel_delta_u,
             pixel_delta_v,
             defocus_disk_u,
             defocus_disk_v,
-        )
+            shutter_open: self.render_options.shutter_open,
+            shutter_close: self.render_options.shutter_close,
+        }
+    }
+
+    /// Renders the world into a framebuffer of linear colors, rays that
+    /// escape the world see the background.
+    pub fn render<T: Hitable>(&self, world: &T, background: &dyn Background) -> Framebuffer {
+        self.render_samples(world, background).image()
     }
 
-    pub fn render<T: Hitable>(&self, world: &T) -> Result<()> {
+    /// Renders the world and returns the samples of every pixel. With a noise
+    /// threshold, pixels stop sampling once their estimate is precise enough.
+    pub fn render_samples<T: Hitable>(
+        &self,
+        world: &T,
+        background: &dyn Background,
+    ) -> Accumulator {
+        let (width, height) = (self.render_options.width, self.render_options.height);
+        let mut accumulator = Accumulator::new(width, height);
+        let progress = ProgressBar::new((width * height) as u64);
+        let samples = self.render_options.samples_per_pixel;
+        self.render_pass(world, background, &mut accumulator, samples, &progress);
+        progress.finish();
+        accumulator
+    }
+
+    /// Adds samples to `accumulator` until every pixel has the requested
+    /// number. This happens in passes over the whole image, see
+    /// `RenderOptions::pass_samples`, and `on_pass` sees the accumulated
+    /// samples after each of them. Rendering stops at the first error
+    /// returned by `on_pass`.
+    pub fn render_passes<T, F>(
+        &self,
+        world: &T,
+        background: &dyn Background,
+        accumulator: &mut Accumulator,
+        mut on_pass: F,
+    ) -> Result<()>
+    where
+        T: Hitable,
+        F: FnMut(&Accumulator) -> Result<()>,
+    {
+        let max_samples = self.render_options.samples_per_pixel;
+        let pass_samples = self.render_options.pass_samples();
+        let first_pass = accumulator
+            .pixels()
+            .iter()
+            .map(PixelAccumulator::samples)
+            .min()
+            .unwrap_or(0)
+            .min(max_samples);
+        let passes = (max_samples - first_pass).div_ceil(pass_samples);
+
         // Set up the progress bar
-        let progress =
-            ProgressBar::new((self.render_options.width * self.render_options.height) as u64);
+        let pixel_count = (accumulator.width() * accumulator.height()) as u64;
+        let progress = ProgressBar::new(pixel_count * passes as u64);
+
+        let mut target = first_pass;
+        while target < max_samples {
+            target = (target + pass_samples).min(max_samples);
+            self.render_pass(world, background, accumulator, target, &progress);
+            on_pass(accumulator)?;
+        }
+        progress.finish();
 
-        // Open the output file
-        let file = File::create(&self.render_options.file_name)?;
-        let mut writer = BufWriter::new(file);
+        Ok(())
+    }
 
+    /// Samples every pixel of the accumulator up to `target` samples.
+    fn render_pass<T: Hitable>(
+        &self,
+        world: &T,
+        background: &dyn Background,
+        accumulator: &mut Accumulator,
+        target: usize,
+        progress: &ProgressBar,
+    ) {
         // Initialize camera parameters
-        let (origin, pixel00_loc, pixel_delta_u, pixel_delta_v, defocus_disk_u, defocus_disk_v) =
-            self.initilize();
+        let frame = self.initilize();
+        let defocus = self.render_options.defocus_angle > 0.0;
 
         // Render
+        let image_width = accumulator.width();
+        let min_samples = self.render_options.min_sample_count();
+
+        // Every sample gets its own random stream, so the result does not
+        // depend on which thread or pass renders it.
+        let seed = self.seed;
+
+        let render_pixel = |i: usize, j: usize, pixel: &mut PixelAccumulator| {
+            let pixel_index = (j * image_width + i) as u64;
+            while pixel.samples() < target {
+                if let Some(threshold) = self.render_options.noise_threshold
+                    && pixel.samples() >= min_samples
+                    && pixel.relative_error() <= threshold
+                {
+                    break;
+                }
+                seed_rng(Camera::sample_seed(
+                    seed,
+                    pixel_index,
+                    pixel.samples() as u64,
+                ));
+
+                let ray = frame.sample_ray(i, j, defocus);
+                pixel.add(ray.color(self.render_options.max_depth, world, background));
+            }
+        };
+
+        let threads = self.render_options.thread_count();
+        for_each_row_parallel(threads, accumulator.rows_mut(), |j, scanline| {
+            for (i, pixel) in scanline.iter_mut().enumerate() {
+                render_pixel(i, j, pixel);
+            }
+            progress.inc(image_width as u64);
+        });
+    }
+
+    /// Records the first surface seen by the camera in every pixel, averaged
+    /// over up to `FEATURE_SAMPLES` of the pixel's samples. These reuse the
+    /// random streams of the first samples of the render, so they follow the
+    /// same camera rays. The object ID is the one of the first sample.
+    pub fn render_features<T: Hitable>(&self, world: &T) -> FeatureBuffer {
         let image_width = self.render_options.width;
         let image_height = self.render_options.height;
+        let samples = self
+            .render_options
+            .samples_per_pixel
+            .clamp(1, FEATURE_SAMPLES);
+        let frame = self.initilize();
+        let defocus = self.render_options.defocus_angle > 0.0;
+        let seed = self.seed;
 
-        writeln!(writer, "P3\n{} {}\n255\n", image_width, image_height)?;
-
-        for j in 0..image_height {
-            for i in 0..image_width {
-                let mut pixel_color = Color::black();
-                for _ in 0..self.render_options.samples_per_pixel {
-                    // Calculate the pixel sample location.
-                    let offset = Vec3::sample_square();
-                    let pixel_sample = pixel00_loc
-                        + (pixel_delta_u * (i as Real + offset.x))
-                        + (pixel_delta_v * (j as Real + offset.y));
-
-                    // Apply defocus if enabled
-                    let ray_origin = origin
-                        + if self.render_options.defocus_angle > 0.0 {
-                            Camera::defocus_disk_sample(defocus_disk_u, defocus_disk_v)
-                        } else {
-                            Vec3::zero()
-                        };
-
-                    let ray_direction = pixel_sample - ray_origin;
-                    let ray = Ray::new(ray_origin, ray_direction);
-
-                    pixel_color += ray.color(self.render_options.max_depth, world);
+        let render_pixel = |i: usize, j: usize| {
+            let pixel_index = (j * image_width + i) as u64;
+            let mut features = Features::default();
+            for sample in 0..samples {
+                seed_rng(Camera::sample_seed(seed, pixel_index, sample as u64));
+                let ray = frame.sample_ray(i, j, defocus);
+                let Some(hit) = world.hit(&ray, &(1e-12..Real::INFINITY)) else {
+                    continue;
+                };
+                features.depth += (hit.p - frame.origin).dot(-frame.w);
+                features.normal += hit.normal;
+                features.albedo += hit.mat.albedo(&hit);
+                features.position += hit.p;
+                if sample == 0 {
+                    features.object_id = hit.object_id;
                 }
-                writeln!(
-                    writer,
-                    "{}",
-                    pixel_color / self.render_options.samples_per_pixel as Real
-                )?;
             }
-            progress.inc(image_width as u64);
-        }
-        progress.finish();
-        writer.flush()?;
-        Ok(())
+            let n = samples as Real;
+            features.depth /= n;
+            features.normal = features.normal / n;
+            features.albedo = features.albedo / n;
+            features.position = features.position / n;
+            features
+        };
+
+        let mut features = FeatureBuffer::new(image_width, image_height);
+        let threads = self.render_options.thread_count();
+        for_each_row_parallel(threads, features.rows_mut(), |j, scanline| {
+            for (i, pixel) in scanline.iter_mut().enumerate() {
+                *pixel = render_pixel(i, j);
+            }
+        });
+        features
+    }
+
+    /// Derives the seed of one sample's random stream by hashing the render
+    /// seed with the pixel and sample indices.
+    fn sample_seed(seed: u64, pixel_index: u64, sample: u64) -> u64 {
+        let pixel_seed = mix64(seed.wrapping_add(pixel_index.wrapping_mul(GOLDEN_GAMMA)));
+        mix64(pixel_seed.wrapping_add(sample.wrapping_mul(GOLDEN_GAMMA)))
     }
 
     fn defocus_disk_sample(defocus_disk_u: Vec3, defocus_disk_v: Vec3) -> Vec3 {
@@ -0,0 +1,89 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    local_frame::{LocalFrame, solve_quadratic, turn},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// All points within `radius` of the segment from `start` to `end`, a
+/// cylinder with hemispheres on both ends. `u` goes around the axis and `v`
+/// from the tip at `start` to the tip at `end`.
+pub struct Capsule<T: Material> {
+    start: Point3,
+    end: Point3,
+    radius: Real,
+    length: Real,
+    frame: LocalFrame,
+    mat: T,
+}
+
+impl<T: Material> Capsule<T> {
+    pub fn new(start: Point3, end: Point3, radius: Real, material: T) -> Self {
+        Capsule {
+            start,
+            end,
+            radius,
+            length: (end - start).length(),
+            frame: LocalFrame::along(start, end - start),
+            mat: material,
+        }
+    }
+}
+
+impl<T: Material> Hitable for Capsule<T> {
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::from_points(self.start, self.end)
+            .union(Aabb::new(self.start - r, self.start + r))
+            .union(Aabb::new(self.end - r, self.end + r))
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+        let r2 = self.radius * self.radius;
+        let mut closest: Option<(Real, Vec3)> = None;
+        let mut consider = |t: Real, normal: Vec3| {
+            if range.contains(&t) && closest.is_none_or(|(closest_t, _)| t < closest_t) {
+                closest = Some((t, normal));
+            }
+        };
+
+        // The side, between the centers of the two hemispheres
+        let a = d.x * d.x + d.z * d.z;
+        let half_b = o.x * d.x + o.z * d.z;
+        let c = o.x * o.x + o.z * o.z - r2;
+        for t in solve_quadratic(a, half_b, c).into_iter().flatten() {
+            let p = local.at(t);
+            if (0.0..=self.length).contains(&p.y) {
+                consider(t, Vec3::new(p.x, 0.0, p.z) / self.radius);
+            }
+        }
+
+        // The hemispheres, each only beyond its end of the segment
+        for (center_y, outer_side) in [(0.0, -1.0), (self.length, 1.0)] {
+            let oc = o - Vec3::new(0.0, center_y, 0.0);
+            let a = d.length_squared();
+            let half_b = oc.dot(d);
+            let c = oc.length_squared() - r2;
+            for t in solve_quadratic(a, half_b, c).into_iter().flatten() {
+                let p = local.at(t);
+                if (p.y - center_y) * outer_side >= 0.0 {
+                    consider(t, (p - Vec3::new(0.0, center_y, 0.0)) / self.radius);
+                }
+            }
+        }
+
+        let (t, normal) = closest?;
+        let p = local.at(t);
+        let v = (p.y + self.radius) / (self.length + 2.0 * self.radius);
+        Some(
+            self.frame
+                .hit_record(ray, t, normal, (turn(p), v.clamp(0.0, 1.0)), &self.mat),
+        )
+    }
+}
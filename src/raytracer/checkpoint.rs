@@ -0,0 +1,164 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    Result,
+    raytracer::{
+        accumulator::{Accumulator, PixelAccumulator},
+        color::Color,
+        options::RenderOptions,
+        vec3::{GOLDEN_GAMMA, Real, mix64},
+    },
+};
+
+const MAGIC: &[u8; 8] = b"RTCKPT02";
+
+/// The state of an unfinished render. The random stream of every sample is
+/// derived from the seed and the sample's index, so the seed is all of the
+/// random number generator state needed to continue. The fingerprint
+/// identifies the scene and settings the samples were rendered with.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub seed: u64,
+    pub fingerprint: u64,
+    pub samples: Accumulator,
+}
+
+/// Returns a hash of everything that decides what a sample sees: the scene's
+/// sources, the contents of the scene file and of every file it loads or a
+/// built-in scene's name, and the camera and render options. The sample
+/// count, adaptive sampling and the output settings are left out, a resumed
+/// render may change them.
+pub fn fingerprint(scene_sources: &[Vec<u8>], options: &RenderOptions) -> u64 {
+    let mut hash: u64 = 0;
+    let mut add = |word: u64| hash = mix64(hash.wrapping_add(GOLDEN_GAMMA) ^ word);
+
+    for source in scene_sources {
+        add(source.len() as u64);
+        for chunk in source.chunks(8) {
+            let mut bytes = [0; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            add(u64::from_le_bytes(bytes));
+        }
+    }
+    for count in [options.width, options.height, options.max_depth] {
+        add(count as u64);
+    }
+    for v in [options.lookfrom, options.lookat, options.vup] {
+        for value in [v.x, v.y, v.z] {
+            add(value.to_bits());
+        }
+    }
+    for value in [
+        options.vertical_fov,
+        options.focus_distance,
+        options.defocus_angle,
+        options.shutter_open,
+        options.shutter_close,
+    ] {
+        add(value.to_bits());
+    }
+    match options.background {
+        Some(Color(color)) => {
+            for value in [1.0, color.x, color.y, color.z] {
+                add(value.to_bits());
+            }
+        }
+        None => add(0),
+    }
+    hash
+}
+
+impl Checkpoint {
+    /// Writes a checkpoint of the samples to `path`. The file is replaced only once the
+    /// new checkpoint is complete, so an interrupted write keeps the old one.
+    pub fn save(
+        path: impl AsRef<Path>,
+        seed: u64,
+        fingerprint: u64,
+        samples: &Accumulator,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        Checkpoint::encode(seed, fingerprint, samples, &mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    fn encode(
+        seed: u64,
+        fingerprint: u64,
+        samples: &Accumulator,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(samples.width() as u64).to_le_bytes())?;
+        writer.write_all(&(samples.height() as u64).to_le_bytes())?;
+        writer.write_all(&seed.to_le_bytes())?;
+        writer.write_all(&fingerprint.to_le_bytes())?;
+        for pixel in samples.pixels() {
+            let sum = pixel.sum.0;
+            for value in [sum.x, sum.y, sum.z, pixel.mean_luminance, pixel.m2] {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+            writer.write_all(&(pixel.samples as u64).to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a checkpoint written by `save`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Checkpoint> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Checkpoint::decode(&data).map_err(|err| format!("{}: {}", path.display(), err).into())
+    }
+
+    fn decode(data: &[u8]) -> Result<Checkpoint> {
+        let mut data = data.strip_prefix(MAGIC).ok_or("Not a render checkpoint")?;
+        let width = read_u64(&mut data)? as usize;
+        let height = read_u64(&mut data)? as usize;
+        let seed = read_u64(&mut data)?;
+        let fingerprint = read_u64(&mut data)?;
+
+        // Every pixel is stored as six 8 byte values.
+        let expected = width.checked_mul(height).and_then(|n| n.checked_mul(48));
+        if width == 0 || height == 0 || expected != Some(data.len()) {
+            return Err("Corrupt checkpoint, the pixel data does not match its size".into());
+        }
+
+        let mut samples = Accumulator::new(width, height);
+        for row in samples.rows_mut() {
+            for pixel in row {
+                let mut real = || read_u64(&mut data).map(Real::from_bits);
+                let sum = Color::new(real()?, real()?, real()?);
+                let mean_luminance = real()?;
+                let m2 = real()?;
+                *pixel = PixelAccumulator {
+                    sum,
+                    samples: read_u64(&mut data)? as usize,
+                    mean_luminance,
+                    m2,
+                };
+            }
+        }
+        Ok(Checkpoint {
+            seed,
+            fingerprint,
+            samples,
+        })
+    }
+}
+
+fn read_u64(data: &mut &[u8]) -> Result<u64> {
+    let (bytes, rest) = data.split_first_chunk().ok_or("Truncated checkpoint")?;
+    *data = rest;
+    Ok(u64::from_le_bytes(*bytes))
+}
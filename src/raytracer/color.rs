@@ -1,11 +1,12 @@
-use core::fmt;
 use std::ops;
 
 use derive_more::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+use serde::Deserialize;
 
 use crate::raytracer::vec3::{Real, Vec3};
 
-#[derive(Debug, Clone, Copy, PartialEq, Add, AddAssign, Sub, SubAssign, Mul, Div)]
+#[derive(Debug, Clone, Copy, PartialEq, Add, AddAssign, Sub, SubAssign, Mul, Div, Deserialize)]
+#[serde(from = "[Real; 3]")]
 pub struct Color(pub(crate) Vec3);
 
 impl Color {
@@ -13,15 +14,38 @@ impl Color {
         Color(Vec3::new(r, g, b))
     }
 
-    fn linear_to_gamma(self) -> Color {
-        Color(self.0.map(|c| if c > 0.0 { c.sqrt() } else { 0.0 }))
+    /// Applies the piecewise sRGB transfer function.
+    pub fn linear_to_srgb(self) -> Color {
+        Color(self.0.map(|c| {
+            if c <= 0.0031308 {
+                12.92 * c.max(0.0)
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        }))
     }
 
-    pub fn to_rgb(self) -> (i32, i32, i32) {
-        let v = (self.linear_to_gamma() * 255.999)
-            .0
-            .map(|c| c.clamp(0.0, 255.0));
-        (v.x as i32, v.y as i32, v.z as i32)
+    /// Inverse of `linear_to_srgb`.
+    pub fn srgb_to_linear(self) -> Color {
+        Color(self.0.map(|c| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }))
+    }
+
+    /// Decodes sRGB encoded integers in `0..=max`, as read from 8 and 16 bit
+    /// images.
+    pub fn dequantize([r, g, b]: [u16; 3], max: u16) -> Self {
+        let max = max as Real;
+        Color::new(r as Real / max, g as Real / max, b as Real / max).srgb_to_linear()
+    }
+
+    /// Relative luminance of the linear Rec. 709 color.
+    pub fn luminance(self) -> Real {
+        0.2126 * self.0.x + 0.7152 * self.0.y + 0.0722 * self.0.z
     }
 
     pub fn lerp(self, other: Color, t: Real) -> Self {
@@ -41,6 +65,12 @@ impl Color {
     }
 }
 
+impl From<[Real; 3]> for Color {
+    fn from([r, g, b]: [Real; 3]) -> Self {
+        Color::new(r, g, b)
+    }
+}
+
 impl ops::Mul<Color> for Color {
     type Output = Self;
 
@@ -53,17 +83,22 @@ impl ops::Mul<Color> for Color {
     }
 }
 
-impl ops::Add<Real> for Color {
+impl ops::Div<Color> for Color {
     type Output = Self;
 
-    fn add(self, scalar: Real) -> Self::Output {
-        Color(self.0 + scalar)
+    fn div(self, other: Color) -> Self::Output {
+        Color::new(
+            self.0.x / other.0.x,
+            self.0.y / other.0.y,
+            self.0.z / other.0.z,
+        )
     }
 }
 
-impl fmt::Display for Color {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (r, g, b) = self.to_rgb();
-        write!(f, "{} {} {}", r, g, b)
+impl ops::Add<Real> for Color {
+    type Output = Self;
+
+    fn add(self, scalar: Real) -> Self::Output {
+        Color(self.0 + scalar)
     }
 }
@@ -0,0 +1,79 @@
+use clap::ValueEnum;
+
+use crate::raytracer::{
+    color::Color,
+    vec3::{Real, Vec3},
+};
+
+/// CIE xy chromaticities of a color space's white point and red, green and
+/// blue primaries
+pub type Chromaticities = [(f32, f32); 4];
+
+/// Color spaces integer images can be encoded in. Renders are computed in
+/// linear sRGB (Rec. 709 primaries).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorSpace {
+    /// sRGB primaries and the piecewise sRGB transfer function
+    #[default]
+    Srgb,
+    /// sRGB primaries without a transfer function
+    Linear,
+    /// Rec. 709 primaries and the Rec. 709 camera transfer function
+    #[value(name = "rec709")]
+    Rec709,
+    /// Display P3 primaries and the sRGB transfer function
+    #[value(name = "display-p3")]
+    DisplayP3,
+}
+
+impl ColorSpace {
+    /// Converts a linear sRGB color with channels in `0..=1` to the encoded
+    /// values of this color space.
+    pub fn encode(self, color: Color) -> Color {
+        match self {
+            ColorSpace::Srgb => color.linear_to_srgb(),
+            ColorSpace::Linear => color,
+            ColorSpace::Rec709 => Color(color.0.map(|c| {
+                if c < 0.018 {
+                    4.5 * c.max(0.0)
+                } else {
+                    1.099 * c.powf(0.45) - 0.099
+                }
+            })),
+            ColorSpace::DisplayP3 => {
+                // Linear sRGB to linear Display P3, both with a D65 white point
+                const SRGB_TO_P3: [[Real; 3]; 3] = [
+                    [0.8224621, 0.1775380, 0.0],
+                    [0.0331941, 0.9668058, 0.0],
+                    [0.0170827, 0.0723974, 0.9105199],
+                ];
+                let v = color.0;
+                let row = |r: [Real; 3]| r[0] * v.x + r[1] * v.y + r[2] * v.z;
+                let p3 = Vec3::new(row(SRGB_TO_P3[0]), row(SRGB_TO_P3[1]), row(SRGB_TO_P3[2]));
+                Color(p3).linear_to_srgb()
+            }
+        }
+    }
+
+    /// Returns the white point and primaries
+    pub fn chromaticities(self) -> Chromaticities {
+        const D65: (f32, f32) = (0.3127, 0.3290);
+        match self {
+            ColorSpace::Srgb | ColorSpace::Linear | ColorSpace::Rec709 => {
+                [D65, (0.64, 0.33), (0.30, 0.60), (0.15, 0.06)]
+            }
+            ColorSpace::DisplayP3 => [D65, (0.680, 0.320), (0.265, 0.690), (0.150, 0.060)],
+        }
+    }
+
+    /// Returns the exponent that approximately decodes the stored values to
+    /// linear light, as used by image metadata
+    pub fn decoding_gamma(self) -> f32 {
+        match self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => 2.2,
+            ColorSpace::Linear => 1.0,
+            // Rec. 709 video is shown on displays following BT.1886.
+            ColorSpace::Rec709 => 2.4,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    local_frame::{LocalFrame, solve_quadratic, turn},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Cone from a disk of `radius` around `base` to the `apex`, closed at the
+/// base unless built `without_cap`. On the side `u` goes around the axis
+/// and `v` from the base to the apex, on the cap `v` runs from the axis to
+/// the rim.
+pub struct Cone<T: Material> {
+    base: Point3,
+    apex: Point3,
+    radius: Real,
+    height: Real,
+    capped: bool,
+    frame: LocalFrame,
+    mat: T,
+}
+
+impl<T: Material> Cone<T> {
+    pub fn new(base: Point3, apex: Point3, radius: Real, material: T) -> Self {
+        Cone {
+            base,
+            apex,
+            radius,
+            height: (apex - base).length(),
+            capped: true,
+            frame: LocalFrame::along(base, apex - base),
+            mat: material,
+        }
+    }
+
+    /// Leaves the base open, the inside is then visible.
+    pub fn without_cap(mut self) -> Self {
+        self.capped = false;
+        self
+    }
+}
+
+impl<T: Material> Hitable for Cone<T> {
+    fn bounding_box(&self) -> Aabb {
+        let axis = (self.apex - self.base) / self.height;
+        Aabb::around_disk(self.base, axis, self.radius).grow(self.apex)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+        let mut closest: Option<(Real, Vec3, (Real, Real))> = None;
+
+        // x² + z² = (k (h - y))², the double cone through the apex
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let to_apex = self.height - o.y;
+        let a = d.x * d.x + d.z * d.z - k2 * d.y * d.y;
+        let half_b = o.x * d.x + o.z * d.z + k2 * to_apex * d.y;
+        let c = o.x * o.x + o.z * o.z - k2 * to_apex * to_apex;
+        for t in solve_quadratic(a, half_b, c).into_iter().flatten() {
+            let p = local.at(t);
+            // The second nappe above the apex is not part of the cone.
+            if range.contains(&t) && (0.0..=self.height).contains(&p.y) {
+                let normal = Vec3::new(p.x, k2 * (self.height - p.y), p.z).normalize();
+                closest = Some((t, normal, (turn(p), p.y / self.height)));
+                break;
+            }
+        }
+
+        if self.capped && d.y.abs() > 1e-12 {
+            let t = -o.y / d.y;
+            let end = closest.map_or(range.end, |(t, ..)| t);
+            let p = local.at(t);
+            let distance_squared = p.x * p.x + p.z * p.z;
+            if (range.start..end).contains(&t) && distance_squared <= self.radius * self.radius {
+                let uv = (turn(p), distance_squared.sqrt() / self.radius);
+                closest = Some((t, Vec3::new(0.0, -1.0, 0.0), uv));
+            }
+        }
+
+        let (t, normal, uv) = closest?;
+        Some(self.frame.hit_record(ray, t, normal, uv, &self.mat))
+    }
+}
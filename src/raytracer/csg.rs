@@ -0,0 +1,233 @@
+use std::ops::Range;
+
+use serde::Deserialize;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    ray::Ray,
+    vec3::{Point3, Real},
+};
+
+/// Gap left after a crossing before looking for the next one, so the same
+/// surface is not found again, relative to the distance of the crossing
+const CROSSING_EPSILON: Real = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsgOperation {
+    /// Everything inside either operand
+    Union,
+    /// Everything inside both operands
+    Intersection,
+    /// Everything inside the first operand but not the second
+    Difference,
+}
+
+impl CsgOperation {
+    fn contains(self, inside_a: bool, inside_b: bool) -> bool {
+        match self {
+            CsgOperation::Union => inside_a || inside_b,
+            CsgOperation::Intersection => inside_a && inside_b,
+            CsgOperation::Difference => inside_a && !inside_b,
+        }
+    }
+}
+
+/// Solid combining two closed objects with a boolean operation. Rays are
+/// followed through the surfaces of both operands, keeping track of which
+/// ones they are inside, and hit the combined solid where that changes.
+///
+/// Operands must be closed with outward normals, so that each crossing tells
+/// whether the ray enters or leaves. An infinite plane works as the half
+/// space behind it. Surfaces keep the material of the operand they belong
+/// to, the subtracted operand's normals are flipped to face out of the hole.
+pub struct Csg {
+    operation: CsgOperation,
+    a: Box<dyn Hitable>,
+    b: Box<dyn Hitable>,
+}
+
+impl Csg {
+    pub fn new(
+        operation: CsgOperation,
+        a: impl Hitable + 'static,
+        b: impl Hitable + 'static,
+    ) -> Self {
+        Csg {
+            operation,
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+
+    pub fn union(a: impl Hitable + 'static, b: impl Hitable + 'static) -> Self {
+        Csg::new(CsgOperation::Union, a, b)
+    }
+
+    pub fn intersection(a: impl Hitable + 'static, b: impl Hitable + 'static) -> Self {
+        Csg::new(CsgOperation::Intersection, a, b)
+    }
+
+    /// Carves `b` out of `a`.
+    pub fn difference(a: impl Hitable + 'static, b: impl Hitable + 'static) -> Self {
+        Csg::new(CsgOperation::Difference, a, b)
+    }
+}
+
+/// The surfaces of a closed object along a ray, visited in order
+pub(crate) struct Crossings<'a, 'r> {
+    object: &'a dyn Hitable,
+    ray: &'r Ray,
+    next: Option<HitRecord<'a>>,
+    /// Whether the ray is inside the object before the next crossing
+    pub inside: bool,
+}
+
+impl<'a, 'r> Crossings<'a, 'r> {
+    pub fn new(object: &'a dyn Hitable, ray: &'r Ray, start: Real) -> Self {
+        let next = object.hit(ray, &(start..Real::INFINITY));
+        // A ray that leaves the object first starts inside of it. One that
+        // never crosses the surface can still be inside an unbounded object.
+        let inside = match &next {
+            Some(hit) => !Crossings::enters(ray, hit),
+            None => object.contains(ray.at(start)).unwrap_or(false),
+        };
+        Crossings {
+            object,
+            ray,
+            next,
+            inside,
+        }
+    }
+
+    fn enters(ray: &Ray, hit: &HitRecord) -> bool {
+        hit.normal.dot(ray.direction) < 0.0
+    }
+
+    pub fn next_t(&self) -> Option<Real> {
+        self.next.as_ref().map(|hit| hit.t)
+    }
+
+    /// Moves past the next crossing and returns it.
+    pub fn advance(&mut self) -> Option<HitRecord<'a>> {
+        let hit = self.next.take()?;
+        self.inside = Crossings::enters(self.ray, &hit);
+        // The gap must move the start even for a crossing at 0.
+        let gap = (hit.t.abs() * CROSSING_EPSILON).max(Real::MIN_POSITIVE);
+        self.next = self.object.hit(self.ray, &(hit.t + gap..Real::INFINITY));
+        Some(hit)
+    }
+}
+
+impl Hitable for Csg {
+    fn bounding_box(&self) -> Aabb {
+        match self.operation {
+            CsgOperation::Union => self.a.bounding_box().union(self.b.bounding_box()),
+            CsgOperation::Intersection => self.a.bounding_box().overlap(self.b.bounding_box()),
+            CsgOperation::Difference => self.a.bounding_box(),
+        }
+    }
+
+    fn contains(&self, p: Point3) -> Option<bool> {
+        Some(
+            self.operation
+                .contains(self.a.contains(p)?, self.b.contains(p)?),
+        )
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let mut a = Crossings::new(&*self.a, ray, range.start);
+        let mut b = Crossings::new(&*self.b, ray, range.start);
+
+        loop {
+            let was_inside = self.operation.contains(a.inside, b.inside);
+            let from_b = match (a.next_t(), b.next_t()) {
+                (Some(t_a), Some(t_b)) => t_b < t_a,
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (None, None) => return None,
+            };
+            let mut hit = if from_b { b.advance()? } else { a.advance()? };
+            if hit.t >= range.end {
+                return None;
+            }
+
+            if self.operation.contains(a.inside, b.inside) != was_inside {
+                if from_b && self.operation == CsgOperation::Difference {
+                    hit.normal = -hit.normal;
+                }
+                return Some(hit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::{
+        color::Color, materials::lambertian::Lambertian, plane::InfinitePlane, sphere::Sphere,
+        vec3::Vec3,
+    };
+
+    fn sphere(x: Real) -> Sphere<Lambertian> {
+        Sphere::new(
+            Point3::new(x, 0.0, 0.0),
+            1.0,
+            Lambertian::new(Color::white()),
+        )
+    }
+
+    /// Shoots a ray along the x axis from `x` and returns where it hits the
+    /// combination of unit spheres at 0 and 1, and the hit's normal.
+    fn hit(operation: CsgOperation, x: Real, direction: Real) -> Option<(Real, Vec3)> {
+        let csg = Csg::new(operation, sphere(0.0), sphere(1.0));
+        let ray = Ray::new(Point3::new(x, 0.0, 0.0), Vec3::new(direction, 0.0, 0.0));
+        let hit = csg.hit(&ray, &(0.0..Real::INFINITY))?;
+        Some((hit.t, hit.normal))
+    }
+
+    fn assert_hit(hit: Option<(Real, Vec3)>, t: Real, normal_x: Real) {
+        let (hit_t, normal) = hit.expect("the ray should hit");
+        assert!((hit_t - t).abs() < 1e-9, "expected t {t}, got {hit_t}");
+        assert!(
+            (normal - Vec3::new(normal_x, 0.0, 0.0)).near_zero(),
+            "expected normal ({normal_x}, 0, 0), got {normal}"
+        );
+    }
+
+    #[test]
+    fn union_hits_the_first_surface() {
+        assert_hit(hit(CsgOperation::Union, -5.0, 1.0), 4.0, -1.0);
+        assert_hit(hit(CsgOperation::Union, 5.0, -1.0), 3.0, 1.0);
+    }
+
+    #[test]
+    fn intersection_hits_the_overlap() {
+        assert_hit(hit(CsgOperation::Intersection, -5.0, 1.0), 5.0, -1.0);
+        assert_hit(hit(CsgOperation::Intersection, 5.0, -1.0), 4.0, 1.0);
+    }
+
+    #[test]
+    fn difference_flips_the_normals_of_the_hole() {
+        assert_hit(hit(CsgOperation::Difference, -5.0, 1.0), 4.0, -1.0);
+        // Entering b first, the ray reaches a where it leaves b.
+        assert_hit(hit(CsgOperation::Difference, 5.0, -1.0), 5.0, 1.0);
+    }
+
+    #[test]
+    fn ray_parallel_to_a_plane_inside_its_half_space() {
+        let material = Lambertian::new(Color::white());
+        let plane = InfinitePlane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), material);
+        let csg = Csg::intersection(sphere(0.0), plane);
+        let ray = Ray::new(Point3::new(-5.0, -0.5, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let hit = csg
+            .hit(&ray, &(0.0..Real::INFINITY))
+            .expect("the ray should hit");
+        assert!((hit.t - (5.0 - 0.75_f64.sqrt())).abs() < 1e-9);
+
+        let ray = Ray::new(Point3::new(-5.0, 0.5, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(csg.hit(&ray, &(0.0..Real::INFINITY)).is_none());
+    }
+}
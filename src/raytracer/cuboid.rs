@@ -0,0 +1,60 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    materials::Material,
+    quad::Quad,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Axis-aligned box made of six quads facing outwards, each textured on its
+/// own. Use an `Instance` to turn it.
+pub struct Cuboid<T: Material> {
+    faces: [Quad<Arc<T>>; 6],
+    bbox: Aabb,
+}
+
+impl<T: Material> Cuboid<T> {
+    /// Creates the box between two opposite corners, in any order.
+    pub fn new(a: Point3, b: Point3, material: T) -> Self {
+        let bbox = Aabb::from_points(a, b);
+        let (min, max) = (bbox.min, bbox.max);
+        let dx = Vec3::new(max.x - min.x, 0.0, 0.0);
+        let dy = Vec3::new(0.0, max.y - min.y, 0.0);
+        let dz = Vec3::new(0.0, 0.0, max.z - min.z);
+
+        let mat = Arc::new(material);
+        let face = |q, u, v| Quad::new(q, u, v, Arc::clone(&mat));
+        Cuboid {
+            faces: [
+                face(min, dy, dx),      // front
+                face(min + dz, dx, dy), // back
+                face(min, dz, dy),      // left
+                face(min + dx, dy, dz), // right
+                face(min, dx, dz),      // bottom
+                face(min + dy, dz, dx), // top
+            ],
+            bbox,
+        }
+    }
+}
+
+impl<T: Material> Hitable for Cuboid<T> {
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let mut closest_hit = None;
+        let mut closest_range = range.clone();
+        for face in &self.faces {
+            if let Some(hit_record) = face.hit(ray, &closest_range) {
+                closest_range = closest_range.start..hit_record.t;
+                closest_hit = Some(hit_record);
+            }
+        }
+        closest_hit
+    }
+}
@@ -0,0 +1,89 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    local_frame::{LocalFrame, solve_quadratic, turn},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Cylinder around the segment from `base` to `top`, closed by flat caps
+/// unless built `without_caps`. On the side `u` goes around the axis and `v`
+/// from the base to the top, on the caps `v` runs from the axis to the rim.
+pub struct Cylinder<T: Material> {
+    base: Point3,
+    top: Point3,
+    radius: Real,
+    height: Real,
+    capped: bool,
+    frame: LocalFrame,
+    mat: T,
+}
+
+impl<T: Material> Cylinder<T> {
+    pub fn new(base: Point3, top: Point3, radius: Real, material: T) -> Self {
+        Cylinder {
+            base,
+            top,
+            radius,
+            height: (top - base).length(),
+            capped: true,
+            frame: LocalFrame::along(base, top - base),
+            mat: material,
+        }
+    }
+
+    /// Leaves both ends open, the inside is then visible.
+    pub fn without_caps(mut self) -> Self {
+        self.capped = false;
+        self
+    }
+}
+
+impl<T: Material> Hitable for Cylinder<T> {
+    fn bounding_box(&self) -> Aabb {
+        let axis = (self.top - self.base) / self.height;
+        Aabb::around_disk(self.base, axis, self.radius).union(Aabb::around_disk(
+            self.top,
+            axis,
+            self.radius,
+        ))
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+        let mut closest: Option<(Real, Vec3, (Real, Real))> = None;
+
+        let a = d.x * d.x + d.z * d.z;
+        let half_b = o.x * d.x + o.z * d.z;
+        let c = o.x * o.x + o.z * o.z - self.radius * self.radius;
+        for t in solve_quadratic(a, half_b, c).into_iter().flatten() {
+            let p = local.at(t);
+            if range.contains(&t) && (0.0..=self.height).contains(&p.y) {
+                let normal = Vec3::new(p.x, 0.0, p.z) / self.radius;
+                closest = Some((t, normal, (turn(p), p.y / self.height)));
+                break;
+            }
+        }
+
+        if self.capped && d.y.abs() > 1e-12 {
+            for (y, normal_y) in [(0.0, -1.0), (self.height, 1.0)] {
+                let t = (y - o.y) / d.y;
+                let end = closest.map_or(range.end, |(t, ..)| t);
+                let p = local.at(t);
+                let distance_squared = p.x * p.x + p.z * p.z;
+                if (range.start..end).contains(&t) && distance_squared <= self.radius * self.radius
+                {
+                    let uv = (turn(p), distance_squared.sqrt() / self.radius);
+                    closest = Some((t, Vec3::new(0.0, normal_y, 0.0), uv));
+                }
+            }
+        }
+
+        let (t, normal, uv) = closest?;
+        Some(self.frame.hit_record(ray, t, normal, uv, &self.mat))
+    }
+}
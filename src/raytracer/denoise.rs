@@ -0,0 +1,157 @@
+use crate::raytracer::{
+    aov::{FeatureBuffer, Features},
+    color::Color,
+    framebuffer::Framebuffer,
+    parallel::for_each_row_parallel,
+    vec3::Real,
+};
+
+/// Joint bilateral filter for noisy renders. Neighbouring pixels are
+/// averaged only where the feature buffers show the same surface, so edges
+/// and textures stay sharp while the noise on each surface is smoothed out.
+#[derive(Debug, Clone)]
+pub struct Denoiser {
+    /// Pixels farther apart than this are never averaged
+    pub radius: usize,
+    pub sigma_spatial: Real,
+    /// Tolerance for luminance differences relative to the sum of both
+    /// luminances, keeps shadow edges
+    pub sigma_luminance: Real,
+    pub sigma_normal: Real,
+    pub sigma_albedo: Real,
+    /// Tolerance for depth differences relative to the depth
+    pub sigma_depth: Real,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Denoiser::new(6)
+    }
+}
+
+impl Denoiser {
+    pub fn new(radius: usize) -> Self {
+        Denoiser {
+            radius,
+            sigma_spatial: radius as Real / 2.0,
+            sigma_luminance: 0.5,
+            sigma_normal: 0.3,
+            sigma_albedo: 0.1,
+            sigma_depth: 0.05,
+        }
+    }
+
+    /// Returns the filtered image, using `threads` worker threads. A radius
+    /// of zero leaves the image unchanged.
+    pub fn denoise(
+        &self,
+        image: &Framebuffer,
+        features: &FeatureBuffer,
+        threads: usize,
+    ) -> Framebuffer {
+        if self.radius == 0 {
+            return image.clone();
+        }
+        let width = image.width();
+        let height = image.height();
+
+        // Filter the light arriving at the surfaces rather than the final
+        // color, so the texture is restored afterwards without blurring.
+        let irradiance: Vec<Color> = image
+            .pixels()
+            .iter()
+            .zip(features.pixels())
+            .map(|(color, features)| *color / Denoiser::albedo(features))
+            .collect();
+        // The luminance term compares slightly smoothed values, single noisy
+        // samples would stop the filter everywhere otherwise.
+        let guide = Denoiser::box_blur(&irradiance, width, height);
+
+        let filter_pixel = |x: usize, y: usize| {
+            let center = y * width + x;
+            let center_features = &features.pixels()[center];
+
+            let mut sum = Color::black();
+            let mut total_weight = 0.0;
+            let (x0, x1) = (
+                x.saturating_sub(self.radius),
+                (x + self.radius).min(width - 1),
+            );
+            let (y0, y1) = (
+                y.saturating_sub(self.radius),
+                (y + self.radius).min(height - 1),
+            );
+            for qy in y0..=y1 {
+                for qx in x0..=x1 {
+                    let q = qy * width + qx;
+                    let q_features = &features.pixels()[q];
+
+                    let dx = qx as Real - x as Real;
+                    let dy = qy as Real - y as Real;
+                    let spatial = (dx * dx + dy * dy) / (self.sigma_spatial * self.sigma_spatial);
+
+                    let luminance =
+                        (guide[q] - guide[center]) / (guide[q] + guide[center]).max(1e-4);
+                    let luminance =
+                        luminance * luminance / (self.sigma_luminance * self.sigma_luminance);
+
+                    let normal = (q_features.normal - center_features.normal).length_squared()
+                        / (self.sigma_normal * self.sigma_normal);
+
+                    let albedo = (q_features.albedo - center_features.albedo)
+                        .0
+                        .length_squared()
+                        / (self.sigma_albedo * self.sigma_albedo);
+
+                    let depth = (q_features.depth - center_features.depth)
+                        / (self.sigma_depth * center_features.depth.abs().max(1e-4));
+                    let depth = depth * depth;
+
+                    let weight = (-0.5 * (spatial + luminance + normal + albedo + depth)).exp();
+                    sum += irradiance[q] * weight;
+                    total_weight += weight;
+                }
+            }
+            // The center pixel always has weight one with a radius of at least
+            // one, so this never divides by zero.
+            sum / total_weight * Denoiser::albedo(center_features)
+        };
+
+        let mut output = Framebuffer::new(width, height);
+        for_each_row_parallel(threads, output.rows_mut(), |y, scanline| {
+            for (x, pixel) in scanline.iter_mut().enumerate() {
+                *pixel = filter_pixel(x, y);
+            }
+        });
+        output
+    }
+
+    /// Returns the albedo the color is divided by, white where there is none
+    /// (lights, the background) so those pixels are filtered as they are.
+    fn albedo(features: &Features) -> Color {
+        let albedo = features.albedo.0;
+        if albedo.x.max(albedo.y).max(albedo.z) < 1e-3 {
+            return Color::white();
+        }
+        Color(albedo.map(|c| c.max(1e-3)))
+    }
+
+    /// Returns the luminance of every pixel averaged with its 3x3 neighbourhood.
+    fn box_blur(pixels: &[Color], width: usize, height: usize) -> Vec<Real> {
+        let mut blurred = Vec::with_capacity(pixels.len());
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                let mut count = 0.0;
+                for qy in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+                    for qx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                        sum += pixels[qy * width + qx].luminance();
+                        count += 1.0;
+                    }
+                }
+                blurred.push(sum / count);
+            }
+        }
+        blurred
+    }
+}
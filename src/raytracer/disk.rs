@@ -0,0 +1,65 @@
+use std::{f64::consts::PI, ops::Range};
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Flat disk facing `normal`. `u` goes once around the center and `v` from
+/// the center out to the rim.
+pub struct Disk<T: Material> {
+    center: Point3,
+    normal: Vec3,
+    radius: Real,
+    /// Directions in the disk's plane, `u` starts along the first one
+    tangent: Vec3,
+    bitangent: Vec3,
+    mat: T,
+}
+
+impl<T: Material> Disk<T> {
+    pub fn new(center: Point3, normal: Vec3, radius: Real, material: T) -> Self {
+        let normal = normal.normalize();
+        let (tangent, bitangent) = normal.orthonormal_basis();
+        Disk {
+            center,
+            normal,
+            radius,
+            tangent,
+            bitangent,
+            mat: material,
+        }
+    }
+}
+
+impl<T: Material> Hitable for Disk<T> {
+    fn bounding_box(&self) -> Aabb {
+        Aabb::around_disk(self.center, self.normal, self.radius)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let denom = self.normal.dot(ray.direction);
+        // The ray is parallel to the disk.
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = self.normal.dot(self.center - ray.origin) / denom;
+        if !range.contains(&t) {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let offset = p - self.center;
+        let distance_squared = offset.length_squared();
+        if distance_squared > self.radius * self.radius {
+            return None;
+        }
+        let angle = offset.dot(self.bitangent).atan2(offset.dot(self.tangent));
+        let u = (angle + PI) / (2.0 * PI);
+        let v = distance_squared.sqrt() / self.radius;
+        Some(HitRecord::new(p, self.normal, t, &self.mat).with_uv(u, v))
+    }
+}
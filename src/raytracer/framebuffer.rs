@@ -0,0 +1,58 @@
+use std::slice::{Chunks, ChunksMut};
+
+use crate::raytracer::color::Color;
+
+/// In-memory image holding linear radiance values in row-major order,
+/// starting at the top left pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Framebuffer {
+    /// Creates a black image of the given size
+    pub fn new(width: usize, height: usize) -> Self {
+        Framebuffer {
+            width,
+            height,
+            pixels: vec![Color::black(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    /// Returns an iterator over the scanlines of the image, top to bottom.
+    /// Images without pixels have no scanlines.
+    pub fn rows(&self) -> Chunks<'_, Color> {
+        self.pixels.chunks(self.width.max(1))
+    }
+
+    /// Returns an iterator over mutable scanlines of the image, top to bottom
+    pub fn rows_mut(&mut self) -> ChunksMut<'_, Color> {
+        self.pixels.chunks_mut(self.width.max(1))
+    }
+}
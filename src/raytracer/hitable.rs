@@ -1,24 +1,110 @@
-use std::ops::Range;
+use std::{ops::Range, sync::Arc};
 
 use crate::raytracer::{
+    aabb::Aabb,
     materials::Material,
     ray::Ray,
     vec3::{Point3, Real, Vec3},
 };
 
-pub trait Hitable {
-    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord>;
+pub trait Hitable: Send + Sync {
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>>;
+
+    /// Returns a box enclosing the whole object
+    fn bounding_box(&self) -> Aabb;
+
+    /// Returns whether `p` is inside the object, for solids a ray can miss
+    /// the surface of while inside, like the half space behind an infinite
+    /// plane. Other objects cannot tell.
+    fn contains(&self, _p: Point3) -> Option<bool> {
+        None
+    }
+}
+
+/// Lets objects be shared, e.g. by several instances.
+impl<H: Hitable + ?Sized> Hitable for Arc<H> {
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, range)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn contains(&self, p: Point3) -> Option<bool> {
+        (**self).contains(p)
+    }
+}
+
+/// Lets wrappers borrow an object, e.g. fog around a world that is also
+/// rendered without it.
+impl<H: Hitable + ?Sized> Hitable for &H {
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, range)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn contains(&self, p: Point3) -> Option<bool> {
+        (**self).contains(p)
+    }
+}
+
+impl<H: Hitable + ?Sized> Hitable for Box<H> {
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, range)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn contains(&self, p: Point3) -> Option<bool> {
+        (**self).contains(p)
+    }
 }
 
 pub struct HitRecord<'a> {
     pub p: Point3,
     pub normal: Vec3,
     pub t: Real,
+    /// Surface coordinates of the hit point, used for texturing
+    pub u: Real,
+    pub v: Real,
     pub mat: &'a dyn Material,
+    /// Position of the hit object in the world counting from 1, set by the
+    /// object lists. Zero means no object.
+    pub object_id: usize,
 }
 
 impl<'a> HitRecord<'a> {
     pub fn new(p: Point3, normal: Vec3, t: Real, mat: &'a dyn Material) -> Self {
-        HitRecord { p, normal, t, mat }
+        HitRecord {
+            p,
+            normal,
+            t,
+            u: 0.0,
+            v: 0.0,
+            mat,
+            object_id: 0,
+        }
+    }
+
+    pub fn with_uv(mut self, u: Real, v: Real) -> Self {
+        self.u = u;
+        self.v = v;
+        self
+    }
+
+    /// Returns the outward normal flipped, if needed, to point against the
+    /// incoming direction. Open surfaces can be hit from either side.
+    pub fn facing_normal(&self, direction: Vec3) -> Vec3 {
+        if self.normal.dot(direction) > 0.0 {
+            -self.normal
+        } else {
+            self.normal
+        }
     }
 }
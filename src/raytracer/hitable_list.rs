@@ -1,6 +1,7 @@
 use std::ops::Range;
 
 use crate::raytracer::{
+    aabb::Aabb,
     hitable::{HitRecord, Hitable},
     ray::Ray,
     vec3::Real,
@@ -8,28 +9,55 @@ use crate::raytracer::{
 
 pub struct HitableList<'a> {
     hitables: Vec<Box<dyn Hitable + 'a>>,
+    bbox: Aabb,
 }
 
 impl<'a> HitableList<'a> {
     pub fn new() -> Self {
         HitableList {
             hitables: Vec::new(),
+            bbox: Aabb::empty(),
         }
     }
 
     pub fn add<T: Hitable + 'a>(&mut self, hitable: T) {
+        self.bbox = self.bbox.union(hitable.bounding_box());
         self.hitables.push(Box::new(hitable));
     }
+
+    pub fn len(&self) -> usize {
+        self.hitables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hitables.is_empty()
+    }
+
+    /// Consumes the list and returns the contained objects
+    pub fn into_hitables(self) -> Vec<Box<dyn Hitable + 'a>> {
+        self.hitables
+    }
+}
+
+impl<'a> Default for HitableList<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> Hitable for HitableList<'a> {
-    fn hit(&self, ray: &Ray, interval: &Range<Real>) -> Option<HitRecord> {
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn hit(&self, ray: &Ray, interval: &Range<Real>) -> Option<HitRecord<'_>> {
         let mut closest_hit: Option<HitRecord> = None;
         let mut closest_interval = interval.clone();
 
-        for hitable in &self.hitables {
-            if let Some(hit_record) = hitable.hit(ray, &closest_interval) {
+        for (index, hitable) in self.hitables.iter().enumerate() {
+            if let Some(mut hit_record) = hitable.hit(ray, &closest_interval) {
                 closest_interval = closest_interval.start..hit_record.t;
+                hit_record.object_id = index + 1;
                 closest_hit = Some(hit_record);
             }
         }
@@ -0,0 +1,159 @@
+use crate::{
+    Result,
+    raytracer::{color::Color, framebuffer::Framebuffer, input::next_token, vec3::Real},
+};
+
+/// Decodes a Radiance RGBE image, flat or with run length encoded scanlines.
+pub fn decode(data: &[u8]) -> Result<Framebuffer> {
+    if !data.starts_with(b"#?") {
+        return Err("Not a Radiance HDR file".into());
+    }
+
+    // The header is a list of lines terminated by an empty one.
+    let mut rest = data;
+    loop {
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or("Unterminated header")?;
+        let line = &rest[..end];
+        rest = &rest[end + 1..];
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix(b"FORMAT=")
+            && format != b"32-bit_rle_rgbe"
+        {
+            return Err(format!(
+                "Unsupported pixel format '{}'",
+                String::from_utf8_lossy(format)
+            )
+            .into());
+        }
+    }
+
+    // Only the standard orientation, top to bottom and left to right.
+    let (y_axis, height, x_axis, width) = (
+        next_token(&mut rest)?,
+        next_token(&mut rest)?,
+        next_token(&mut rest)?,
+        next_token(&mut rest)?,
+    );
+    if y_axis != "-Y" || x_axis != "+X" {
+        return Err(format!("Unsupported orientation '{} {}'", y_axis, x_axis).into());
+    }
+    let width: usize = width.parse()?;
+    let height: usize = height.parse()?;
+    rest = rest.get(1..).ok_or("Missing image data")?;
+
+    // Run length encoding stores at most 127 values of a component in two
+    // bytes, so no pixel takes less than a sixteenth of a byte.
+    let pixels = width.checked_mul(height).ok_or("Image size is too large")?;
+    if pixels.max(width) / 16 > rest.len() {
+        return Err("The image data is too short for its size".into());
+    }
+
+    let mut image = Framebuffer::new(width, height);
+    let mut scanline = vec![[0u8; 4]; width];
+    for row in image.rows_mut() {
+        rest = read_scanline(rest, &mut scanline)?;
+        for (pixel, rgbe) in row.iter_mut().zip(&scanline) {
+            *pixel = rgbe_to_color(*rgbe);
+        }
+    }
+    Ok(image)
+}
+
+fn rgbe_to_color([r, g, b, e]: [u8; 4]) -> Color {
+    if e == 0 {
+        return Color::black();
+    }
+    // Mantissas are stored as fractions of 256 sharing one exponent.
+    let scale = (2.0 as Real).powi(e as i32 - 136);
+    Color::new(r as Real * scale, g as Real * scale, b as Real * scale)
+}
+
+/// Reads one scanline into `scanline` and returns the remaining data.
+fn read_scanline<'a>(data: &'a [u8], scanline: &mut [[u8; 4]]) -> Result<&'a [u8]> {
+    let width = scanline.len();
+    let truncated = "Unexpected end of image data";
+
+    // New style run length encoding starts with 2, 2 and the width.
+    let is_rle = (8..0x8000).contains(&width)
+        && data.len() >= 4
+        && data[0] == 2
+        && data[1] == 2
+        && data[2] & 0x80 == 0;
+    if !is_rle {
+        let bytes = data.get(..width * 4).ok_or(truncated)?;
+        for (pixel, rgbe) in scanline.iter_mut().zip(bytes.chunks_exact(4)) {
+            *pixel = [rgbe[0], rgbe[1], rgbe[2], rgbe[3]];
+        }
+        return Ok(&data[width * 4..]);
+    }
+    if ((data[2] as usize) << 8 | data[3] as usize) != width {
+        return Err("Scanline width does not match the image width".into());
+    }
+
+    // Each of the four components is encoded separately.
+    let mut data = &data[4..];
+    for component in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let (&count, rest) = data.split_first().ok_or(truncated)?;
+            if count > 128 {
+                // A run of one repeated value
+                let count = count as usize - 128;
+                let (&value, rest) = rest.split_first().ok_or(truncated)?;
+                if x + count > width {
+                    return Err("Run exceeds the scanline".into());
+                }
+                for pixel in &mut scanline[x..x + count] {
+                    pixel[component] = value;
+                }
+                x += count;
+                data = rest;
+            } else {
+                // A sequence of literal values
+                let count = count as usize;
+                if count == 0 || x + count > width {
+                    return Err("Invalid literal run length".into());
+                }
+                let values = rest.get(..count).ok_or(truncated)?;
+                for (pixel, &value) in scanline[x..x + count].iter_mut().zip(values) {
+                    pixel[component] = value;
+                }
+                x += count;
+                data = &rest[count..];
+            }
+        }
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hdr(size: &str, pixels: &[u8]) -> Vec<u8> {
+        let mut data = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n{}\n", size).into_bytes();
+        data.extend_from_slice(pixels);
+        data
+    }
+
+    #[test]
+    fn decodes_flat_pixels() {
+        let image = decode(&hdr("-Y 1 +X 2", &[128, 64, 0, 129, 0, 0, 0, 0])).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 1));
+    }
+
+    #[test]
+    fn rejects_sizes_the_data_cannot_fill() {
+        let oversized = hdr("-Y 4000000000 +X 4000000000", &[0; 16]);
+        assert!(decode(&oversized).is_err());
+        let truncated = hdr("-Y 100 +X 100", &[0; 16]);
+        assert!(decode(&truncated).is_err());
+        let overflowing = hdr("-Y 18446744073709551615 +X 2", &[0; 16]);
+        assert!(decode(&overflowing).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fs, path::Path};
+
+use crate::{Result, raytracer::framebuffer::Framebuffer};
+
+/// Image file formats that can be read, detected from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Radiance RGBE
+    Hdr,
+    /// Portable Float Map
+    Pfm,
+    Png,
+    /// Binary or plain text Portable Pixmap
+    Ppm,
+}
+
+impl InputFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("hdr") => Ok(InputFormat::Hdr),
+            Some("pfm") => Ok(InputFormat::Pfm),
+            Some("png") => Ok(InputFormat::Png),
+            Some("ppm") => Ok(InputFormat::Ppm),
+            _ => Err(format!(
+                "Unsupported image format for '{}', expected .hdr, .pfm, .png or .ppm",
+                path.display()
+            )
+            .into()),
+        }
+    }
+}
+
+/// Loads an image into a framebuffer of linear colors. Integer formats are
+/// decoded with the inverse of the gamma used when writing them.
+pub fn load_image(path: impl AsRef<Path>) -> Result<Framebuffer> {
+    let path = path.as_ref();
+    let format = InputFormat::from_path(path)?;
+    let data =
+        fs::read(path).map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+    let image = match format {
+        InputFormat::Hdr => hdr::decode(&data),
+        InputFormat::Pfm => pfm::decode(&data),
+        InputFormat::Png => png::decode(&data),
+        InputFormat::Ppm => ppm::decode(&data),
+    }
+    .map_err(|err| format!("Failed to decode '{}': {}", path.display(), err))?;
+    // Textures and environment maps look up pixels, so there must be some.
+    if image.width() == 0 || image.height() == 0 {
+        return Err(format!("'{}' is an empty image", path.display()).into());
+    }
+    Ok(image)
+}
+
+/// Splits off the next whitespace separated token of a text header.
+fn next_token<'a>(data: &mut &'a [u8]) -> Result<&'a str> {
+    let start = data
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .ok_or("Unexpected end of header")?;
+    let rest = &data[start..];
+    let end = rest
+        .iter()
+        .position(|b| b.is_ascii_whitespace())
+        .unwrap_or(rest.len());
+    let token = std::str::from_utf8(&rest[..end])?;
+    *data = &rest[end..];
+    Ok(token)
+}
+
+pub mod hdr;
+pub mod pfm;
+pub mod png;
+pub mod ppm;
@@ -0,0 +1,62 @@
+use crate::{
+    Result,
+    raytracer::{color::Color, framebuffer::Framebuffer, input::next_token, vec3::Real},
+};
+
+/// Decodes a color (`PF`) or grayscale (`Pf`) Portable Float Map.
+pub fn decode(mut data: &[u8]) -> Result<Framebuffer> {
+    let channels = match next_token(&mut data)? {
+        "PF" => 3,
+        "Pf" => 1,
+        magic => return Err(format!("Not a PFM file, unexpected magic '{}'", magic).into()),
+    };
+    let width: usize = next_token(&mut data)?.parse()?;
+    let height: usize = next_token(&mut data)?.parse()?;
+    let scale: f32 = next_token(&mut data)?.parse()?;
+    // Exactly one whitespace character separates the header from the samples.
+    let data = data.get(1..).ok_or("Missing image data")?;
+
+    let expected = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(channels * 4))
+        .ok_or("Image size is too large")?;
+    if data.len() < expected {
+        return Err(format!(
+            "Expected {} bytes of image data, found {}",
+            expected,
+            data.len()
+        )
+        .into());
+    }
+    // A negative scale marks the samples as little endian.
+    let sample = |i: usize| {
+        let bytes = [
+            data[i * 4],
+            data[i * 4 + 1],
+            data[i * 4 + 2],
+            data[i * 4 + 3],
+        ];
+        let value = if scale < 0.0 {
+            f32::from_le_bytes(bytes)
+        } else {
+            f32::from_be_bytes(bytes)
+        };
+        value as Real
+    };
+
+    // Scanlines are stored bottom to top.
+    let mut image = Framebuffer::new(width, height);
+    for (y, row) in image.rows_mut().enumerate() {
+        let first = (height - 1 - y) * width;
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let i = (first + x) * channels;
+            *pixel = if channels == 3 {
+                Color::new(sample(i), sample(i + 1), sample(i + 2))
+            } else {
+                let v = sample(i);
+                Color::new(v, v, v)
+            };
+        }
+    }
+    Ok(image)
+}
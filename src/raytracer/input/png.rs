@@ -0,0 +1,54 @@
+use std::io::Cursor;
+
+use png::{ColorType, Decoder, Transformations};
+
+use crate::{
+    Result,
+    raytracer::{color::Color, framebuffer::Framebuffer},
+};
+
+/// Decodes a PNG image of any color type, ignoring transparency.
+pub fn decode(data: &[u8]) -> Result<Framebuffer> {
+    let mut decoder = Decoder::new(Cursor::new(data));
+    // Expand palettes and low bit depths to 8 bit samples.
+    decoder.set_transformations(Transformations::EXPAND);
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size().ok_or("Image is too large")?];
+    let info = reader.next_frame(&mut buffer)?;
+
+    let channels = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => return Err("Unexpected indexed colors".into()),
+    };
+    let sixteen_bit = info.bit_depth == png::BitDepth::Sixteen;
+    let max = if sixteen_bit {
+        u16::MAX
+    } else {
+        u8::MAX as u16
+    };
+    let sample = |row: &[u8], i: usize| {
+        if sixteen_bit {
+            u16::from_be_bytes([row[2 * i], row[2 * i + 1]])
+        } else {
+            row[i] as u16
+        }
+    };
+
+    let mut image = Framebuffer::new(info.width as usize, info.height as usize);
+    let lines = buffer.chunks(info.line_size);
+    for (row, line) in image.rows_mut().zip(lines) {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let i = x * channels;
+            let rgb = if channels < 3 {
+                [sample(line, i); 3]
+            } else {
+                [sample(line, i), sample(line, i + 1), sample(line, i + 2)]
+            };
+            *pixel = Color::dequantize(rgb, max);
+        }
+    }
+    Ok(image)
+}
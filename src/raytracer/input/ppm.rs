@@ -0,0 +1,53 @@
+use crate::{
+    Result,
+    raytracer::{color::Color, framebuffer::Framebuffer, input::next_token},
+};
+
+/// Decodes a binary (`P6`) or plain text (`P3`) Portable Pixmap.
+pub fn decode(mut data: &[u8]) -> Result<Framebuffer> {
+    let plain = match next_token(&mut data)? {
+        "P3" => true,
+        "P6" => false,
+        magic => return Err(format!("Not a PPM file, unexpected magic '{}'", magic).into()),
+    };
+    let width: usize = next_token(&mut data)?.parse()?;
+    let height: usize = next_token(&mut data)?.parse()?;
+    let max: u16 = next_token(&mut data)?.parse()?;
+    if max == 0 {
+        return Err("Maximum value must be positive".into());
+    }
+
+    let count = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(3))
+        .ok_or("Image size is too large")?;
+    let samples: Vec<u16> = if plain {
+        (0..count)
+            .map(|_| Ok(next_token(&mut data)?.parse()?))
+            .collect::<Result<_>>()?
+    } else {
+        // Exactly one whitespace character separates the header from the samples.
+        let data = data.get(1..).ok_or("Missing image data")?;
+        let sample_size = if max > u8::MAX as u16 { 2 } else { 1 };
+        let data = data
+            .get(
+                ..count
+                    .checked_mul(sample_size)
+                    .ok_or("Image size is too large")?,
+            )
+            .ok_or("Unexpected end of image data")?;
+        if sample_size == 2 {
+            data.chunks_exact(2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .collect()
+        } else {
+            data.iter().map(|&b| b as u16).collect()
+        }
+    };
+
+    let mut image = Framebuffer::new(width, height);
+    for (pixel, rgb) in image.pixels_mut().iter_mut().zip(samples.chunks_exact(3)) {
+        *pixel = Color::dequantize([rgb[0], rgb[1], rgb[2]], max);
+    }
+    Ok(image)
+}
@@ -0,0 +1,91 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    Result,
+    raytracer::{
+        aabb::Aabb,
+        hitable::{HitRecord, Hitable},
+        matrix::Matrix4,
+        ray::Ray,
+        vec3::{Point3, Real},
+    },
+};
+
+/// An object placed in the world by a transform. Instances share the object,
+/// so placing a mesh many times does not copy its geometry.
+pub struct Instance {
+    object: Arc<dyn Hitable>,
+    to_world: Matrix4,
+    to_object: Matrix4,
+    /// Inverse transpose of `to_world`, keeps normals perpendicular to
+    /// surfaces under non-uniform scaling
+    normal_to_world: Matrix4,
+    bbox: Aabb,
+}
+
+impl Instance {
+    /// Places `object` with `transform`, which has to be invertible.
+    pub fn new(object: Arc<dyn Hitable>, transform: Matrix4) -> Result<Self> {
+        let to_object = transform
+            .inverse()
+            .ok_or("The transform of an instance must be invertible")?;
+
+        // Transforming infinite corners gives NaNs, unbounded objects stay unbounded.
+        let object_bbox = object.bounding_box();
+        let bbox = if object_bbox.is_unbounded() {
+            Aabb::infinite()
+        } else {
+            Instance::transformed_bbox(&object_bbox, &transform)
+        };
+
+        Ok(Instance {
+            object,
+            to_world: transform,
+            to_object,
+            normal_to_world: to_object.transpose(),
+            bbox,
+        })
+    }
+
+    /// Returns the box around the transformed corners of the object's box
+    fn transformed_bbox(object_bbox: &Aabb, transform: &Matrix4) -> Aabb {
+        (0..8).fold(Aabb::empty(), |bbox, corner| {
+            let pick = |bit: usize, axis: usize| {
+                if corner & bit == 0 {
+                    object_bbox.min[axis]
+                } else {
+                    object_bbox.max[axis]
+                }
+            };
+            let p = Point3::new(pick(1, 0), pick(2, 1), pick(4, 2));
+            bbox.grow(transform.transform_point(p))
+        })
+    }
+}
+
+impl Hitable for Instance {
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn contains(&self, p: Point3) -> Option<bool> {
+        self.object.contains(self.to_object.transform_point(p))
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        // The direction is not normalized, so distances along the ray
+        // are the same in both spaces.
+        let local_ray = Ray::with_time(
+            self.to_object.transform_point(ray.origin),
+            self.to_object.transform_vector(ray.direction),
+            ray.time,
+        );
+        let mut hit = self.object.hit(&local_ray, range)?;
+        hit.p = self.to_world.transform_point(hit.p);
+        hit.normal = self
+            .normal_to_world
+            .transform_vector(hit.normal)
+            .normalize();
+        Some(hit)
+    }
+}
@@ -0,0 +1,83 @@
+use std::f64::consts::PI;
+
+use crate::raytracer::{
+    hitable::HitRecord,
+    materials::Material,
+    matrix::Matrix4,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Rigid frame in which a shape built around an axis is intersected. The
+/// shape's axis is the local y axis.
+pub(crate) struct LocalFrame {
+    to_world: Matrix4,
+    to_local: Matrix4,
+}
+
+impl LocalFrame {
+    /// Returns the frame with its origin at `origin` and its y axis along
+    /// `axis`, or the world's y axis for a zero `axis`.
+    pub fn along(origin: Point3, axis: Vec3) -> Self {
+        let y = if axis.near_zero() {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            axis.normalize()
+        };
+        let (x, z) = y.orthonormal_basis();
+        let rotation = Matrix4::new([
+            [x.x, y.x, z.x, 0.0],
+            [x.y, y.y, z.y, 0.0],
+            [x.z, y.z, z.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        // The rotation is orthonormal, its inverse is its transpose.
+        let to_world = Matrix4::translation(origin) * rotation;
+        let to_local = rotation.transpose() * Matrix4::translation(-origin);
+        LocalFrame { to_world, to_local }
+    }
+
+    /// Returns the ray in local coordinates. Distances along it stay the
+    /// same.
+    pub fn to_local(&self, ray: &Ray) -> Ray {
+        Ray::with_time(
+            self.to_local.transform_point(ray.origin),
+            self.to_local.transform_vector(ray.direction),
+            ray.time,
+        )
+    }
+
+    /// Returns the record of a hit at `t` along the world space ray, with
+    /// the normal given in local coordinates.
+    pub fn hit_record<'a>(
+        &self,
+        ray: &Ray,
+        t: Real,
+        normal: Vec3,
+        (u, v): (Real, Real),
+        mat: &'a dyn Material,
+    ) -> HitRecord<'a> {
+        let normal = self.to_world.transform_vector(normal).normalize();
+        HitRecord::new(ray.at(t), normal, t, mat).with_uv(u, v)
+    }
+}
+
+/// Returns the angle of a local point around the y axis as a fraction of a
+/// full turn.
+pub(crate) fn turn(p: Point3) -> Real {
+    (p.z.atan2(p.x) + PI) / (2.0 * PI)
+}
+
+/// Returns the roots of `a t² + 2 half_b t + c`, smallest first.
+pub(crate) fn solve_quadratic(a: Real, half_b: Real, c: Real) -> Option<[Real; 2]> {
+    if a.abs() < 1e-12 {
+        return None;
+    }
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let sqrt_d = discriminant.sqrt();
+    let (t0, t1) = ((-half_b - sqrt_d) / a, (-half_b + sqrt_d) / a);
+    Some([t0.min(t1), t0.max(t1)])
+}
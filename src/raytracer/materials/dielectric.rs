@@ -51,7 +51,12 @@ impl Material for Dielectric {
             unit_direction.refract(normal, ri)
         };
 
-        let scattered = Ray::new(hit.p, direction);
+        let scattered = Ray::with_time(hit.p, direction, ray.time);
         Some((scattered, Color::white()))
     }
+
+    /// Clear glass lets all light through.
+    fn albedo(&self, _hit: &HitRecord) -> Color {
+        Color::white()
+    }
 }
@@ -0,0 +1,21 @@
+use crate::raytracer::{color::Color, hitable::HitRecord, materials::Material, ray::Ray};
+
+/// Area light, emits the same radiance in every direction and reflects nothing.
+pub struct DiffuseLight {
+    emit: Color,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        DiffuseLight { emit }
+    }
+}
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: &Ray, _hit: &HitRecord) -> Option<(Ray, Color)> {
+        None
+    }
+
+    fn emitted(&self, _hit: &HitRecord) -> Color {
+        self.emit
+    }
+}
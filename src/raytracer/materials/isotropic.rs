@@ -0,0 +1,38 @@
+use crate::raytracer::{
+    color::Color,
+    hitable::HitRecord,
+    materials::Material,
+    ray::Ray,
+    textures::{Texture, solid_color::SolidColor},
+    vec3::Vec3,
+};
+
+/// Phase function of participating media, scatters light equally in every
+/// direction.
+pub struct Isotropic {
+    albedo: Box<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Isotropic::textured(SolidColor::new(albedo))
+    }
+
+    pub fn textured(albedo: impl Texture + 'static) -> Self {
+        Isotropic {
+            albedo: Box::new(albedo),
+        }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color)> {
+        let new_ray = Ray::with_time(hit.p, Vec3::random_unit(), ray.time);
+        let attenuation = self.albedo.value(hit.u, hit.v, hit.p);
+        Some((new_ray, attenuation))
+    }
+
+    fn albedo(&self, hit: &HitRecord) -> Color {
+        self.albedo.value(hit.u, hit.v, hit.p)
+    }
+}
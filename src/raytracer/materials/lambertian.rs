@@ -1,23 +1,40 @@
 use crate::raytracer::{
-    color::Color, hitable::HitRecord, materials::Material, ray::Ray, vec3::Vec3,
+    color::Color,
+    hitable::HitRecord,
+    materials::Material,
+    ray::Ray,
+    textures::{Texture, solid_color::SolidColor},
+    vec3::Vec3,
 };
 
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Box<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
-        Lambertian { albedo }
+        Lambertian::textured(SolidColor::new(albedo))
+    }
+
+    pub fn textured(albedo: impl Texture + 'static) -> Self {
+        Lambertian {
+            albedo: Box::new(albedo),
+        }
     }
 }
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color)> {
-        let mut direction = hit.normal + Vec3::random_unit();
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color)> {
+        let normal = hit.facing_normal(ray.direction);
+        let mut direction = normal + Vec3::random_unit();
         if direction.near_zero() {
-            direction = hit.normal; // Handle near-zero direction to avoid NaN
+            direction = normal; // Handle near-zero direction to avoid NaN
         }
-        let new_ray = Ray::new(hit.p, direction);
-        Some((new_ray, self.albedo))
+        let new_ray = Ray::with_time(hit.p, direction, ray.time);
+        let attenuation = self.albedo.value(hit.u, hit.v, hit.p);
+        Some((new_ray, attenuation))
+    }
+
+    fn albedo(&self, hit: &HitRecord) -> Color {
+        self.albedo.value(hit.u, hit.v, hit.p)
     }
 }
@@ -3,27 +3,41 @@ use crate::raytracer::{
     hitable::HitRecord,
     materials::Material,
     ray::Ray,
+    textures::{Texture, solid_color::SolidColor},
     vec3::{Real, Vec3},
 };
 
 pub struct Metal {
-    albedo: Color,
+    albedo: Box<dyn Texture>,
     fuzziness: Real,
 }
 
 impl Metal {
     pub fn new(albedo: Color, fuzziness: Real) -> Self {
-        Metal { albedo, fuzziness }
+        Metal::textured(SolidColor::new(albedo), fuzziness)
+    }
+
+    pub fn textured(albedo: impl Texture + 'static, fuzziness: Real) -> Self {
+        Metal {
+            albedo: Box::new(albedo),
+            fuzziness,
+        }
     }
 }
 impl Material for Metal {
     fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color)> {
+        let normal = hit.facing_normal(ray.direction);
         let direction =
-            ray.direction.reflect(hit.normal).normalize() + Vec3::random_unit() * self.fuzziness;
-        if direction.near_zero() || direction.dot(hit.normal) < 0.0 {
+            ray.direction.reflect(normal).normalize() + Vec3::random_unit() * self.fuzziness;
+        if direction.near_zero() || direction.dot(normal) < 0.0 {
             return None; // Ray is absorbed
         }
-        let new_ray = Ray::new(hit.p, direction);
-        Some((new_ray, self.albedo))
+        let new_ray = Ray::with_time(hit.p, direction, ray.time);
+        let attenuation = self.albedo.value(hit.u, hit.v, hit.p);
+        Some((new_ray, attenuation))
+    }
+
+    fn albedo(&self, hit: &HitRecord) -> Color {
+        self.albedo.value(hit.u, hit.v, hit.p)
     }
 }
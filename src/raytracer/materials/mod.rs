@@ -1,10 +1,40 @@
+use std::sync::Arc;
+
 use crate::raytracer::{color::Color, hitable::HitRecord, ray::Ray};
 
-pub trait Material {
+pub trait Material: Send + Sync {
     /// Returns the scattered ray and the attenuation color.
     fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<(Ray, Color)>;
+
+    /// Returns the light emitted from the hit point, black for most materials.
+    fn emitted(&self, _hit_record: &HitRecord) -> Color {
+        Color::black()
+    }
+
+    /// Returns the surface color at the hit point, as seen by the albedo
+    /// output. Black for materials without one.
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        Color::black()
+    }
+}
+
+/// Lets objects share one material, e.g. all faces of a mesh.
+impl<M: Material + ?Sized> Material for Arc<M> {
+    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<(Ray, Color)> {
+        (**self).scatter(ray, hit_record)
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Color {
+        (**self).emitted(hit_record)
+    }
+
+    fn albedo(&self, hit_record: &HitRecord) -> Color {
+        (**self).albedo(hit_record)
+    }
 }
 
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
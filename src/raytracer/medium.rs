@@ -0,0 +1,205 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    color::Color,
+    csg::Crossings,
+    hitable::{HitRecord, Hitable},
+    materials::isotropic::Isotropic,
+    ray::Ray,
+    textures::Texture,
+    vec3::{Real, Vec3},
+};
+
+/// Returns a random distance to the next scattering event in a medium of
+/// `density`, exponentially distributed.
+fn free_path(density: Real) -> Real {
+    // 1 - x lies in 0..=1 without 0, keeping the logarithm finite.
+    -(1.0 - Vec3::random_real()).ln() / density
+}
+
+/// Returns the record of a ray scattering in a medium at `t`. Media have no
+/// surface, the normal faces back along the ray.
+fn scatter_record<'a>(ray: &Ray, t: Real, phase: &'a Isotropic) -> HitRecord<'a> {
+    HitRecord::new(ray.at(t), -ray.direction.normalize(), t, phase)
+}
+
+/// Smoke or mist of constant density filling a closed boundary. Rays are
+/// scattered at a random depth inside, the denser the medium the sooner.
+pub struct ConstantMedium<B: Hitable> {
+    boundary: B,
+    density: Real,
+    phase: Isotropic,
+}
+
+impl<B: Hitable> ConstantMedium<B> {
+    pub fn new(boundary: B, density: Real, albedo: Color) -> Self {
+        ConstantMedium {
+            boundary,
+            density,
+            phase: Isotropic::new(albedo),
+        }
+    }
+
+    pub fn textured(boundary: B, density: Real, albedo: impl Texture + 'static) -> Self {
+        ConstantMedium {
+            boundary,
+            density,
+            phase: Isotropic::textured(albedo),
+        }
+    }
+}
+
+impl<B: Hitable> Hitable for ConstantMedium<B> {
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let ray_length = ray.direction.length();
+        let mut remaining = free_path(self.density);
+
+        // Walk through the parts of the ray inside the boundary, which may
+        // be several for concave shapes, until the free path is used up.
+        let mut crossings = Crossings::new(&self.boundary, ray, range.start);
+        let mut t = range.start;
+        loop {
+            let end = crossings.next_t().unwrap_or(Real::INFINITY).min(range.end);
+            if crossings.inside {
+                let distance_inside = (end - t) * ray_length;
+                if remaining < distance_inside {
+                    let t = t + remaining / ray_length;
+                    return Some(scatter_record(ray, t, &self.phase));
+                }
+                remaining -= distance_inside;
+            }
+            if end >= range.end {
+                return None;
+            }
+            crossings.advance();
+            t = end;
+        }
+    }
+}
+
+/// Homogeneous fog filling the world, or only the part below a height.
+/// Rays leaving the world above the fog see the background, rays leaving it
+/// inside the fog are scattered before they get far, so distant objects and
+/// the horizon fade into the light scattered by the fog.
+pub struct Fog {
+    density: Real,
+    height: Real,
+    phase: Isotropic,
+}
+
+impl Fog {
+    /// Returns fog filling all of space. Only rays that find a light before
+    /// they scatter too often bring any light then.
+    pub fn new(density: Real, albedo: Color) -> Self {
+        Fog {
+            density,
+            height: Real::INFINITY,
+            phase: Isotropic::new(albedo),
+        }
+    }
+
+    /// Limits the fog to the space below `height`.
+    pub fn below(self, height: Real) -> Self {
+        Fog { height, ..self }
+    }
+
+    /// Fills `world` with the fog.
+    pub fn around<H: Hitable>(self, world: H) -> Foggy<H> {
+        Foggy { world, fog: self }
+    }
+
+    /// Returns the part of `range` along the ray that lies in the fog.
+    fn clip(&self, ray: &Ray, range: Range<Real>) -> Range<Real> {
+        let (origin, direction) = (ray.origin.y, ray.direction.y);
+        let crossing = (self.height - origin) / direction;
+        if direction > 0.0 {
+            range.start..range.end.min(crossing)
+        } else if direction < 0.0 {
+            range.start.max(crossing)..range.end
+        } else if origin < self.height {
+            range
+        } else {
+            range.start..range.start
+        }
+    }
+}
+
+/// A world filled with fog, see `Fog`
+pub struct Foggy<H: Hitable> {
+    world: H,
+    fog: Fog,
+}
+
+impl<H: Hitable> Hitable for Foggy<H> {
+    fn bounding_box(&self) -> Aabb {
+        self.world.bounding_box()
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let hit = self.world.hit(ray, range);
+        let end = hit.as_ref().map_or(range.end, |hit| hit.t);
+        let fog = self.fog.clip(ray, range.start..end);
+        let t = fog.start + free_path(self.fog.density) / ray.direction.length();
+        if t < fog.end {
+            Some(scatter_record(ray, t, &self.fog.phase))
+        } else {
+            hit
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::{
+        hitable_list::HitableList,
+        materials::lambertian::Lambertian,
+        sphere::Sphere,
+        vec3::{Point3, Vec3},
+    };
+
+    /// Shoots a ray at a sphere 100 units away through fog of `density`
+    /// and returns where it stops.
+    fn stop(density: Real) -> Real {
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, -100.0),
+            1.0,
+            Lambertian::new(Color::white()),
+        );
+        let world = Fog::new(density, Color::white()).around(sphere);
+        let ray = Ray::new(Point3::zero(), Vec3::new(0.0, 0.0, -1.0));
+        world
+            .hit(&ray, &(0.001..Real::INFINITY))
+            .expect("the ray should stop")
+            .t
+    }
+
+    #[test]
+    fn clear_fog_keeps_the_world_hit() {
+        assert_eq!(stop(0.0), 99.0);
+    }
+
+    #[test]
+    fn dense_fog_scatters_before_the_world_hit() {
+        assert!(stop(1e3) < 1.0);
+    }
+
+    #[test]
+    fn fog_stays_below_its_height() {
+        let fog = Fog::new(1e3, Color::white()).below(0.0);
+        let world = fog.around(HitableList::default());
+        let up = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert!(world.hit(&up, &(0.001..Real::INFINITY)).is_none());
+        let down = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let t = world
+            .hit(&down, &(0.001..Real::INFINITY))
+            .expect("the ray should scatter")
+            .t;
+        assert!((1.0..1.1).contains(&t));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::raytracer::{
+    aabb::Aabb,
+    bvh::Bvh,
+    hitable::{HitRecord, Hitable},
+    hitable_list::HitableList,
+    materials::Material,
+    ray::Ray,
+    triangle::intersect_triangle,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Vertex buffers, shared by every mesh that indexes into them.
+#[derive(Debug, Default)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<(Real, Real)>,
+}
+
+/// One corner of a face, as indices into the vertex buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshVertex {
+    pub position: usize,
+    pub normal: Option<usize>,
+    pub uv: Option<usize>,
+}
+
+/// A triangle of a mesh, `material` indexes into the mesh's materials.
+#[derive(Debug, Clone, Copy)]
+pub struct MeshFace {
+    pub vertices: [MeshVertex; 3],
+    pub material: usize,
+}
+
+/// Indexed triangle mesh with its own bounding volume hierarchy.
+pub struct Mesh {
+    name: String,
+    triangle_count: usize,
+    triangles: Bvh<'static>,
+}
+
+impl Mesh {
+    /// Builds a mesh from faces indexing into `data`. Panics if a face refers
+    /// to a vertex or material that does not exist.
+    pub fn new(
+        name: impl Into<String>,
+        data: Arc<MeshData>,
+        faces: &[MeshFace],
+        materials: &[Arc<dyn Material>],
+    ) -> Self {
+        let mut triangles = HitableList::new();
+        for face in faces {
+            triangles.add(MeshTriangle::new(
+                data.clone(),
+                face.vertices,
+                materials[face.material].clone(),
+            ));
+        }
+        Mesh {
+            name: name.into(),
+            triangle_count: faces.len(),
+            triangles: Bvh::new(triangles),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangle_count
+    }
+}
+
+impl Hitable for Mesh {
+    fn bounding_box(&self) -> Aabb {
+        self.triangles.bounding_box()
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        self.triangles.hit(ray, range)
+    }
+}
+
+struct MeshTriangle {
+    data: Arc<MeshData>,
+    vertices: [MeshVertex; 3],
+    mat: Arc<dyn Material>,
+}
+
+impl MeshTriangle {
+    fn new(data: Arc<MeshData>, vertices: [MeshVertex; 3], mat: Arc<dyn Material>) -> Self {
+        MeshTriangle {
+            data,
+            vertices,
+            mat,
+        }
+    }
+
+    fn positions(&self) -> [Point3; 3] {
+        self.vertices
+            .map(|vertex| self.data.positions[vertex.position])
+    }
+}
+
+impl Hitable for MeshTriangle {
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.positions();
+        Aabb::from_points(a, b).grow(c)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let [a, b, c] = self.positions();
+        let (t, u, v) = intersect_triangle(ray, a, b, c, range)?;
+
+        // Interpolate the vertex normals when every corner has one.
+        let normal = match self.vertices.map(|vertex| vertex.normal) {
+            [Some(na), Some(nb), Some(nc)] => {
+                let normals = &self.data.normals;
+                (normals[na] * (1.0 - u - v) + normals[nb] * u + normals[nc] * v).normalize()
+            }
+            _ => (b - a).cross(c - a).normalize(),
+        };
+
+        // Interpolate the texture coordinates, or fall back to barycentrics.
+        let (tu, tv) = match self.vertices.map(|vertex| vertex.uv) {
+            [Some(ta), Some(tb), Some(tc)] => {
+                let uvs = &self.data.uvs;
+                let w = 1.0 - u - v;
+                (
+                    uvs[ta].0 * w + uvs[tb].0 * u + uvs[tc].0 * v,
+                    uvs[ta].1 * w + uvs[tb].1 * u + uvs[tc].1 * v,
+                )
+            }
+            _ => (u, v),
+        };
+
+        Some(HitRecord::new(ray.at(t), normal, t, self.mat.as_ref()).with_uv(tu, tv))
+    }
+}
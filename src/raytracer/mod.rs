@@ -1,9 +1,42 @@
+pub mod aabb;
+pub mod accumulator;
+pub mod aov;
+pub mod background;
+pub mod bvh;
 pub mod camera;
+pub mod capsule;
+pub mod checkpoint;
 pub mod color;
+pub mod color_space;
+pub mod cone;
+pub mod csg;
+pub mod cuboid;
+pub mod cylinder;
+pub mod denoise;
+pub mod disk;
+pub mod framebuffer;
 pub mod hitable;
 pub mod hitable_list;
+pub mod input;
+pub mod instance;
+pub mod local_frame;
 pub mod materials;
+pub mod matrix;
+pub mod medium;
+pub mod mesh;
+pub mod obj;
 pub mod options;
+pub mod output;
+pub mod parallel;
+pub mod plane;
+pub mod quad;
 pub mod ray;
+pub mod scene;
+pub mod scene_file;
+pub mod scenes;
 pub mod sphere;
+pub mod textures;
+pub mod tone_mapping;
+pub mod torus;
+pub mod triangle;
 pub mod vec3;
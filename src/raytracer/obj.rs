@@ -0,0 +1,284 @@
+//! Wavefront OBJ and MTL loading.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    Result,
+    raytracer::{
+        color::Color,
+        input::load_image,
+        materials::{
+            Material, dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
+            metal::Metal,
+        },
+        mesh::{Mesh, MeshData, MeshFace, MeshVertex},
+        textures::image::ImageTexture,
+        vec3::{Real, Vec3},
+    },
+};
+
+/// Loads an OBJ file and the material libraries it references. Returns one
+/// mesh per group, all sharing the same vertex buffers. Faces without a
+/// material use `default_material`. The paths of all files read are added to
+/// `files`.
+pub fn load_obj(
+    path: impl AsRef<Path>,
+    default_material: Arc<dyn Material>,
+    files: &mut Vec<PathBuf>,
+) -> Result<Vec<Mesh>> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+    files.push(path.to_path_buf());
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut data = MeshData::default();
+    let mut groups: Vec<(String, Vec<MeshFace>)> = vec![("default".to_string(), Vec::new())];
+    let mut library: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    let mut materials = vec![default_material];
+    let mut material_indices: HashMap<String, usize> = HashMap::new();
+    let mut current_material = 0;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let fail = |message: String| format!("{}:{}: {}", path.display(), line_index + 1, message);
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        match keyword {
+            "v" => data.positions.push(parse_vec3(&mut tokens).map_err(fail)?),
+            "vn" => data.normals.push(parse_vec3(&mut tokens).map_err(fail)?),
+            "vt" => {
+                let u = parse_real(tokens.next()).map_err(fail)?;
+                let v = tokens.next().map_or(Ok(0.0), |v| parse_real(Some(v)));
+                data.uvs.push((u, v.map_err(fail)?));
+            }
+            "f" => {
+                let corners = tokens
+                    .map(|corner| parse_corner(corner, &data))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(fail)?;
+                if corners.len() < 3 {
+                    return Err(fail("A face needs at least three vertices".to_string()).into());
+                }
+                // Triangulate polygons as a fan around the first corner.
+                let faces = &mut groups.last_mut().unwrap().1;
+                for i in 1..corners.len() - 1 {
+                    faces.push(MeshFace {
+                        vertices: [corners[0], corners[i], corners[i + 1]],
+                        material: current_material,
+                    });
+                }
+            }
+            "g" | "o" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                let group = groups.last_mut().unwrap();
+                if group.1.is_empty() {
+                    group.0 = name;
+                } else {
+                    groups.push((name, Vec::new()));
+                }
+            }
+            "mtllib" => {
+                for file_name in tokens {
+                    let materials = load_mtl(&directory.join(file_name), files)
+                        .map_err(|err| fail(format!("Failed to load material library: {}", err)))?;
+                    library.extend(materials);
+                }
+            }
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                current_material = match material_indices.get(&name) {
+                    Some(&index) => index,
+                    None => {
+                        let material = library
+                            .get(&name)
+                            .ok_or_else(|| fail(format!("Unknown material '{}'", name)))?;
+                        materials.push(material.clone());
+                        material_indices.insert(name, materials.len() - 1);
+                        materials.len() - 1
+                    }
+                };
+            }
+            // Smoothing groups, comments and other statements are ignored.
+            _ => {}
+        }
+    }
+
+    let data = Arc::new(data);
+    Ok(groups
+        .into_iter()
+        .filter(|(_, faces)| !faces.is_empty())
+        .map(|(name, faces)| Mesh::new(name, data.clone(), &faces, &materials))
+        .collect())
+}
+
+/// Material parameters as written in an MTL file.
+struct MtlMaterial {
+    diffuse: Color,
+    diffuse_map: Option<ImageTexture>,
+    specular: Color,
+    emission: Color,
+    shininess: Real,
+    refraction_index: Real,
+    dissolve: Real,
+    illumination: u32,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        MtlMaterial {
+            diffuse: Color::new(0.8, 0.8, 0.8),
+            diffuse_map: None,
+            specular: Color::black(),
+            emission: Color::black(),
+            shininess: 0.0,
+            refraction_index: 1.5,
+            dissolve: 1.0,
+            illumination: 2,
+        }
+    }
+}
+
+impl MtlMaterial {
+    /// Maps the parameters onto the closest of our materials.
+    fn into_material(self) -> Arc<dyn Material> {
+        let transparent = self.dissolve < 1.0 || matches!(self.illumination, 4 | 6 | 7 | 9);
+        let reflective = matches!(self.illumination, 3 | 5 | 8);
+        if self.emission != Color::black() {
+            Arc::new(DiffuseLight::new(self.emission))
+        } else if transparent {
+            Arc::new(Dielectric::new(self.refraction_index))
+        } else if reflective {
+            // Rough approximation of a Phong exponent as a fuzz radius.
+            let fuzziness = (2.0 / (self.shininess + 2.0)).sqrt().min(1.0);
+            Arc::new(Metal::new(self.specular, fuzziness))
+        } else if let Some(diffuse_map) = self.diffuse_map {
+            Arc::new(Lambertian::textured(diffuse_map))
+        } else {
+            Arc::new(Lambertian::new(self.diffuse))
+        }
+    }
+}
+
+/// Loads all materials defined in an MTL file, by name. The paths of all
+/// files read are added to `files`.
+pub fn load_mtl(
+    path: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<HashMap<String, Arc<dyn Material>>> {
+    let source = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+    files.push(path.to_path_buf());
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut parsed: Vec<(String, MtlMaterial)> = Vec::new();
+    for (line_index, line) in source.lines().enumerate() {
+        let fail = |message: String| format!("{}:{}: {}", path.display(), line_index + 1, message);
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            parsed.push((name, MtlMaterial::default()));
+            continue;
+        }
+        let Some((_, material)) = parsed.last_mut() else {
+            if keyword.starts_with('#') {
+                continue;
+            }
+            return Err(fail(format!("'{}' before any 'newmtl'", keyword)).into());
+        };
+        match keyword {
+            "Kd" => material.diffuse = parse_vec3(&mut tokens).map_err(fail)?.to_color(),
+            "Ks" => material.specular = parse_vec3(&mut tokens).map_err(fail)?.to_color(),
+            "Ke" => material.emission = parse_vec3(&mut tokens).map_err(fail)?.to_color(),
+            "Ns" => material.shininess = parse_real(tokens.next()).map_err(fail)?,
+            "Ni" => material.refraction_index = parse_real(tokens.next()).map_err(fail)?,
+            "d" => material.dissolve = parse_real(tokens.next()).map_err(fail)?,
+            "Tr" => material.dissolve = 1.0 - parse_real(tokens.next()).map_err(fail)?,
+            "map_Kd" => {
+                // Texture options come before the file name, which ends the line.
+                let file_name = tokens
+                    .last()
+                    .ok_or_else(|| fail("Missing file name".into()))?;
+                let path = directory.join(file_name);
+                let image = load_image(&path)
+                    .map_err(|err| fail(format!("Failed to load texture: {}", err)))?;
+                files.push(path);
+                material.diffuse_map = Some(ImageTexture::new(image));
+            }
+            "illum" => {
+                material.illumination = parse_real(tokens.next()).map_err(fail)? as u32;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(parsed
+        .into_iter()
+        .map(|(name, material)| (name, material.into_material()))
+        .collect())
+}
+
+fn parse_real(token: Option<&str>) -> std::result::Result<Real, String> {
+    let token = token.ok_or("Missing number")?;
+    token
+        .parse()
+        .map_err(|_| format!("Invalid number '{}'", token))
+}
+
+fn parse_vec3<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> std::result::Result<Vec3, String> {
+    Ok(Vec3::new(
+        parse_real(tokens.next())?,
+        parse_real(tokens.next())?,
+        parse_real(tokens.next())?,
+    ))
+}
+
+/// Parses a face corner such as `1`, `1/2`, `1//3` or `1/2/3`.
+fn parse_corner(corner: &str, data: &MeshData) -> std::result::Result<MeshVertex, String> {
+    let mut indices = corner.split('/');
+    let position = resolve_index(indices.next(), data.positions.len(), "position")?
+        .ok_or_else(|| format!("Missing position index in '{}'", corner))?;
+    let uv = resolve_index(indices.next(), data.uvs.len(), "texture coordinate")?;
+    let normal = resolve_index(indices.next(), data.normals.len(), "normal")?;
+    Ok(MeshVertex {
+        position,
+        normal,
+        uv,
+    })
+}
+
+/// Converts a one-based, possibly negative (relative) index into a zero-based
+/// one. Returns `None` when the index is left out.
+fn resolve_index(
+    token: Option<&str>,
+    count: usize,
+    kind: &str,
+) -> std::result::Result<Option<usize>, String> {
+    let Some(token) = token.filter(|token| !token.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("Invalid {} index '{}'", kind, token))?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(format!("{} index {} is out of range", kind, index));
+    }
+    Ok(Some(resolved as usize))
+}
@@ -1,18 +1,31 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
-use clap::{Args, Parser};
+use clap::{ArgMatches, Args, Parser, builder::RangedU64ValueParser, parser::ValueSource};
 
-use crate::raytracer::vec3::{Point3, Real, Vec3};
+use crate::raytracer::{
+    aov::Aov,
+    color::Color,
+    color_space::ColorSpace,
+    output::{BitDepth, EncodeOptions},
+    scene::SceneCamera,
+    scenes::scene_name_parser,
+    tone_mapping::{ToneMapper, ToneMapping},
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Samples per pixel of each pass when rendering with checkpoints but
+/// without progressive mode
+const CHECKPOINT_PASS_SAMPLES: usize = 16;
 
 /// Rendering options for the ray tracer.
 #[derive(Debug, Args)]
 pub struct RenderOptions {
     /// Width of the image
-    #[arg(short = 'W', long = "width", default_value_t = 1920)]
+    #[arg(short = 'W', long = "width", default_value_t = 1920, value_parser = at_least_one())]
     pub width: usize,
 
     /// Height of the image
-    #[arg(short = 'H', long = "height", default_value_t = 1080)]
+    #[arg(short = 'H', long = "height", default_value_t = 1080, value_parser = at_least_one())]
     pub height: usize,
 
     /// Vertical field of view in degrees
@@ -20,9 +33,19 @@ pub struct RenderOptions {
     pub vertical_fov: Real,
 
     /// Number of samples per pixel
-    #[arg(short = 's', long = "samples", default_value_t = 100)]
+    #[arg(short = 's', long = "samples", default_value_t = 100, value_parser = at_least_one())]
     pub samples_per_pixel: usize,
 
+    /// Stop sampling a pixel once the standard error of its mean luminance,
+    /// relative to the mean, falls below this value. The sample count then
+    /// becomes a maximum. Every pixel gets all samples when unset
+    #[arg(long = "noise-threshold")]
+    pub noise_threshold: Option<Real>,
+
+    /// Number of samples every pixel gets before the noise threshold is checked
+    #[arg(long = "min-samples", default_value_t = 16)]
+    pub min_samples: usize,
+
     /// Maximum depth of ray bounces
     #[arg(short = 'd', long = "max-depth", default_value_t = 50)]
     pub max_depth: usize,
@@ -40,9 +63,134 @@ pub struct RenderOptions {
     #[arg(short = 'a', long = "defocus-angle", default_value_t = 0.6)]
     pub defocus_angle: Real,
 
-    /// Output file name
+    /// Time the shutter opens, moving objects are blurred over the time it is open
+    #[arg(
+        long = "shutter-open",
+        default_value_t = 0.0,
+        allow_negative_numbers = true
+    )]
+    pub shutter_open: Real,
+
+    /// Time the shutter closes, no motion blur when equal to the opening time
+    #[arg(
+        long = "shutter-close",
+        default_value_t = 0.0,
+        allow_negative_numbers = true
+    )]
+    pub shutter_close: Real,
+
+    /// Solid background color (r,g,b) for rays that hit nothing, instead of
+    /// the scene's background or the default sky gradient
+    #[arg(long = "background")]
+    pub background: Option<Color>,
+
+    /// Seed for all random sampling, renders with the same seed are identical.
+    /// A random seed is used when unset
+    #[arg(long = "seed")]
+    pub seed: Option<u64>,
+
+    /// Render in passes of this many samples per pixel over the whole image,
+    /// writing the output image after each pass
+    #[arg(long = "progressive", value_name = "SAMPLES")]
+    pub progressive: Option<usize>,
+
+    /// Write the output image at most this often in progressive mode instead
+    /// of after every pass
+    #[arg(
+        long = "snapshot-interval",
+        value_name = "SECONDS",
+        requires = "progressive"
+    )]
+    pub snapshot_interval: Option<Real>,
+
+    /// Save the render state to this file between passes, so that it can be
+    /// continued with --resume
+    #[arg(long = "checkpoint", value_name = "PATH")]
+    pub checkpoint: Option<String>,
+
+    /// Minimum time between checkpoints
+    #[arg(
+        long = "checkpoint-interval",
+        value_name = "SECONDS",
+        default_value_t = 300.0
+    )]
+    pub checkpoint_interval: Real,
+
+    /// Continue the render saved in a checkpoint until every pixel has the
+    /// requested number of samples. Later checkpoints replace this file unless
+    /// --checkpoint is given
+    #[arg(long = "resume", value_name = "CHECKPOINT")]
+    pub resume: Option<String>,
+
+    /// Number of worker threads, 0 uses all available cores
+    #[arg(short = 't', long = "threads", default_value_t = 0)]
+    pub threads: usize,
+
+    /// Output file name, the format is chosen from the extension (.ppm, .png, .pfm or .exr)
     #[arg(short = 'o', long = "output", default_value = "image.ppm")]
     pub file_name: String,
+
+    /// Bits per channel of the output image [default: 8, or 32 for float formats]
+    #[arg(short = 'b', long = "bit-depth")]
+    pub bit_depth: Option<BitDepth>,
+
+    /// Write plain text (P3) instead of binary (P6) PPM files
+    #[arg(long = "plain-ppm")]
+    pub plain_ppm: bool,
+
+    /// Exposure adjustment in stops, each stop doubles the brightness
+    #[arg(
+        long = "exposure",
+        default_value_t = 0.0,
+        allow_negative_numbers = true
+    )]
+    pub exposure: Real,
+
+    /// Operator mapping bright colors into the range of PPM and PNG images,
+    /// float formats keep the linear colors
+    #[arg(long = "tone-map", value_enum, default_value_t = ToneMapping::Clamp)]
+    pub tone_mapping: ToneMapping,
+
+    /// Luminance that becomes white with the extended Reinhard operator
+    #[arg(long = "white-point", default_value_t = 4.0)]
+    pub white_point: Real,
+
+    /// Color space of PPM and PNG images, float formats are always linear sRGB
+    #[arg(long = "color-space", value_enum, default_value_t = ColorSpace::Srgb)]
+    pub color_space: ColorSpace,
+
+    /// Do not dither 8 bit images
+    #[arg(long = "no-dither")]
+    pub no_dither: bool,
+
+    /// Smooth out the noise of the image with a filter guided by the albedo,
+    /// normal and depth of the visible surfaces
+    #[arg(long = "denoise")]
+    pub denoise: bool,
+
+    /// Largest distance in pixels over which the denoiser averages
+    #[arg(
+        long = "denoise-radius",
+        default_value_t = 6,
+        requires = "denoise",
+        value_parser = at_least_one()
+    )]
+    pub denoise_radius: usize,
+
+    /// Extra passes to write, as layers of EXR images or as files next to
+    /// the image otherwise (e.g. image.depth.png). Can be repeated
+    #[arg(long = "aov", value_delimiter = ',')]
+    pub aovs: Vec<Aov>,
+
+    /// Also write an image of the samples taken per pixel, from blue for the
+    /// fewest to red for the maximum
+    #[arg(long = "sample-heatmap")]
+    pub sample_heatmap: Option<String>,
+}
+
+/// Parses counts that must be at least one
+fn at_least_one() -> RangedU64ValueParser<usize> {
+    RangedU64ValueParser::new().range(1..)
 }
 
 impl FromStr for Vec3 {
@@ -66,12 +214,133 @@ impl FromStr for Vec3 {
     }
 }
 
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Vec3::from_str(s).map(Vec3::to_color)
+    }
+}
+
 impl RenderOptions {
     /// Returns the aspect ratio of the image
     pub fn aspect_ratio(&self) -> Real {
         self.width as Real / self.height as Real
     }
 
+    /// Returns the number of worker threads to render with
+    pub fn thread_count(&self) -> usize {
+        if self.threads > 0 {
+            self.threads
+        } else {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        }
+    }
+
+    /// Returns the file checkpoints are written to, if any
+    pub fn checkpoint_path(&self) -> Option<&str> {
+        self.checkpoint.as_deref().or(self.resume.as_deref())
+    }
+
+    /// Returns the number of samples per pixel of each pass over the image.
+    /// Checkpoints can only be taken between passes, so checkpointed renders
+    /// are split into passes even when not progressive
+    pub fn pass_samples(&self) -> usize {
+        match self.progressive {
+            Some(samples) => samples.max(1),
+            None if self.checkpoint_path().is_some() => CHECKPOINT_PASS_SAMPLES,
+            None => self.samples_per_pixel.max(1),
+        }
+    }
+
+    /// Returns the number of samples every pixel gets, at most the sample count
+    pub fn min_sample_count(&self) -> usize {
+        match self.noise_threshold {
+            Some(_) => self.min_samples.clamp(1, self.samples_per_pixel),
+            None => self.samples_per_pixel,
+        }
+    }
+
+    /// Replaces options left at their defaults with the scene's camera
+    /// settings. Options given on the command line take precedence. Fails
+    /// if the scene's settings cannot be rendered.
+    pub fn apply_scene_camera(
+        &mut self,
+        camera: &SceneCamera,
+        matches: &ArgMatches,
+    ) -> crate::Result<()> {
+        let is_default = |id: &str| {
+            !matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        };
+        macro_rules! apply {
+            ($($field:ident),*) => {
+                $(
+                    if let Some(value) = camera.$field
+                        && is_default(stringify!($field))
+                    {
+                        self.$field = value;
+                    }
+                )*
+            };
+        }
+        apply!(
+            width,
+            height,
+            vertical_fov,
+            samples_per_pixel,
+            max_depth,
+            lookfrom,
+            lookat,
+            vup,
+            focus_distance,
+            defocus_angle,
+            shutter_open,
+            shutter_close
+        );
+        self.validate()
+    }
+
+    /// Checks the options the command line parser cannot check, as the
+    /// scene's camera settings may have replaced them.
+    fn validate(&self) -> crate::Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!(
+                "The image must be at least 1x1 pixels, not {}x{}",
+                self.width, self.height
+            )
+            .into());
+        }
+        if self.samples_per_pixel == 0 {
+            return Err("Every pixel needs at least one sample".into());
+        }
+        if self.shutter_close < self.shutter_open {
+            return Err(format!(
+                "The shutter closes at {} before it opens at {}",
+                self.shutter_close, self.shutter_open
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the settings used to encode the output image
+    pub fn encode_options(&self) -> EncodeOptions {
+        EncodeOptions {
+            bit_depth: self.bit_depth,
+            plain_ppm: self.plain_ppm,
+            tone_mapper: ToneMapper {
+                operator: self.tone_mapping,
+                exposure: self.exposure,
+                white_point: self.white_point,
+            },
+            color_space: self.color_space,
+            dither: !self.no_dither,
+        }
+    }
+
     /// Returns the viewport height based on the fov
     pub fn viewport_height(&self) -> Real {
         let h = (self.vertical_fov.to_radians() / 2.0).tan();
@@ -92,4 +361,66 @@ impl RenderOptions {
 pub struct Options {
     #[command(flatten)]
     pub render: RenderOptions,
+
+    /// Scene file (TOML) to render instead of a built-in scene. Its camera
+    /// settings replace the defaults of the options above
+    #[arg(long = "scene", conflicts_with = "scene_name")]
+    pub scene: Option<PathBuf>,
+
+    /// Built-in scene to render, its camera settings replace the defaults of
+    /// the options above
+    #[arg(long = "scene-name", default_value = "final", value_parser = scene_name_parser())]
+    pub scene_name: String,
+
+    /// List the built-in scenes and exit
+    #[arg(long = "list-scenes")]
+    pub list_scenes: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> clap::error::Result<(Options, ArgMatches)> {
+        let matches = Options::command().try_get_matches_from(args)?;
+        Ok((Options::from_arg_matches(&matches)?, matches))
+    }
+
+    #[test]
+    fn rejects_zero_samples() {
+        assert!(parse(&["render", "-s", "0", "--noise-threshold", "0.1"]).is_err());
+
+        let (mut options, matches) = parse(&["render", "--noise-threshold", "0.1"]).unwrap();
+        let camera = SceneCamera {
+            samples_per_pixel: Some(0),
+            ..SceneCamera::default()
+        };
+        assert!(
+            options
+                .render
+                .apply_scene_camera(&camera, &matches)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_a_shutter_closing_before_it_opens() {
+        let (mut options, matches) =
+            parse(&["render", "--shutter-open", "1", "--shutter-close", "0.5"]).unwrap();
+        let camera = SceneCamera::default();
+        assert!(
+            options
+                .render
+                .apply_scene_camera(&camera, &matches)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn clamps_the_minimum_samples_to_the_sample_count() {
+        let (options, _) = parse(&["render", "-s", "4", "--noise-threshold", "0.1"]).unwrap();
+        assert_eq!(options.render.min_sample_count(), 4);
+    }
 }
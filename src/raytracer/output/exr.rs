@@ -0,0 +1,148 @@
+use std::io::Write;
+
+use half::f16;
+
+use crate::{
+    Result,
+    raytracer::{
+        framebuffer::Framebuffer,
+        output::{BitDepth, ImageEncoder},
+    },
+};
+
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+/// Version 2, single part scanline image with short attribute names.
+const VERSION: [u8; 4] = [2, 0, 0, 0];
+
+const PIXEL_TYPE_HALF: i32 = 1;
+const PIXEL_TYPE_FLOAT: i32 = 2;
+
+/// A named channel of linear samples in row-major order
+pub struct ExrChannel<'a> {
+    pub name: String,
+    pub samples: Box<dyn Fn(usize) -> f32 + 'a>,
+}
+
+impl<'a> ExrChannel<'a> {
+    pub fn new(name: impl Into<String>, samples: impl Fn(usize) -> f32 + 'a) -> Self {
+        ExrChannel {
+            name: name.into(),
+            samples: Box::new(samples),
+        }
+    }
+}
+
+/// Uncompressed scanline OpenEXR encoder, writing R, G and B channels as
+/// half (16 bit) or full (32 bit) floats
+pub struct ExrEncoder {
+    pub bit_depth: BitDepth,
+}
+
+impl ExrEncoder {
+    /// Writes an image made of arbitrary named channels, all of the same size.
+    pub fn encode_channels(
+        &self,
+        width: usize,
+        height: usize,
+        channels: &mut [ExrChannel],
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        let (pixel_type, sample_size) = match self.bit_depth {
+            BitDepth::Sixteen => (PIXEL_TYPE_HALF, 2),
+            BitDepth::ThirtyTwo => (PIXEL_TYPE_FLOAT, 4),
+            BitDepth::Eight => return Err("EXR supports 16 or 32 bits per channel".into()),
+        };
+        // Readers expect the channel list in alphabetical order.
+        channels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&VERSION);
+
+        let mut channel_list = Vec::new();
+        for channel in channels.iter() {
+            channel_list.extend_from_slice(channel.name.as_bytes());
+            channel_list.push(0);
+            channel_list.extend_from_slice(&pixel_type.to_le_bytes());
+            // Not perceptually linear, followed by three reserved bytes.
+            channel_list.extend_from_slice(&[0, 0, 0, 0]);
+            channel_list.extend_from_slice(&1i32.to_le_bytes());
+            channel_list.extend_from_slice(&1i32.to_le_bytes());
+        }
+        channel_list.push(0);
+        write_attribute(&mut header, "channels", "chlist", &channel_list);
+
+        write_attribute(&mut header, "compression", "compression", &[0]);
+        let mut window = Vec::new();
+        for v in [0, 0, width as i32 - 1, height as i32 - 1] {
+            window.extend_from_slice(&v.to_le_bytes());
+        }
+        write_attribute(&mut header, "dataWindow", "box2i", &window);
+        write_attribute(&mut header, "displayWindow", "box2i", &window);
+        write_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+        write_attribute(
+            &mut header,
+            "pixelAspectRatio",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        write_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+        write_attribute(
+            &mut header,
+            "screenWindowWidth",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        header.push(0);
+        writer.write_all(&header)?;
+
+        // Every scanline is its own chunk, so the offsets are known up front.
+        let line_size = width * channels.len() * sample_size;
+        let chunk_size = 8 + line_size;
+        let first_chunk = header.len() + height * 8;
+        for y in 0..height {
+            let offset = (first_chunk + y * chunk_size) as u64;
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+
+        let mut line = Vec::with_capacity(line_size);
+        for y in 0..height {
+            line.clear();
+            for channel in channels.iter() {
+                for x in 0..width {
+                    let sample = (channel.samples)(y * width + x);
+                    if pixel_type == PIXEL_TYPE_HALF {
+                        line.extend_from_slice(&f16::from_f32(sample).to_le_bytes());
+                    } else {
+                        line.extend_from_slice(&sample.to_le_bytes());
+                    }
+                }
+            }
+            writer.write_all(&(y as i32).to_le_bytes())?;
+            writer.write_all(&(line_size as i32).to_le_bytes())?;
+            writer.write_all(&line)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
+
+impl ImageEncoder for ExrEncoder {
+    fn encode(&self, image: &Framebuffer, writer: &mut dyn Write) -> Result<()> {
+        let pixels = image.pixels();
+        let mut channels = [
+            ExrChannel::new("R", |i| pixels[i].0.x as f32),
+            ExrChannel::new("G", |i| pixels[i].0.y as f32),
+            ExrChannel::new("B", |i| pixels[i].0.z as f32),
+        ];
+        self.encode_channels(image.width(), image.height(), &mut channels, writer)
+    }
+}
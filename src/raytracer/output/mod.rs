@@ -0,0 +1,219 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    Result,
+    raytracer::{
+        color::Color,
+        color_space::ColorSpace,
+        framebuffer::Framebuffer,
+        output::{exr::ExrEncoder, pfm::PfmEncoder, png::PngEncoder, ppm::PpmEncoder},
+        tone_mapping::ToneMapper,
+        vec3::{GOLDEN_GAMMA, Real, mix64},
+    },
+};
+
+pub trait ImageEncoder {
+    /// Writes the image to the writer in the encoder's format.
+    fn encode(&self, image: &Framebuffer, writer: &mut dyn Write) -> Result<()>;
+}
+
+/// Number of bits stored per color channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BitDepth {
+    #[value(name = "8")]
+    Eight,
+    #[value(name = "16")]
+    Sixteen,
+    #[value(name = "32")]
+    ThirtyTwo,
+}
+
+impl BitDepth {
+    /// Returns the largest value an integer channel can hold, `None` for
+    /// the float depth
+    pub fn max_value(self) -> Option<u16> {
+        match self {
+            BitDepth::Eight => Some(u8::MAX as u16),
+            BitDepth::Sixteen => Some(u16::MAX),
+            BitDepth::ThirtyTwo => None,
+        }
+    }
+
+    pub fn bits(self) -> u32 {
+        match self {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => 16,
+            BitDepth::ThirtyTwo => 32,
+        }
+    }
+}
+
+/// Settings shared by the image encoders
+#[derive(Debug, Clone, Default)]
+pub struct EncodeOptions {
+    /// Bits per channel, each format picks its own default when unset
+    pub bit_depth: Option<BitDepth>,
+    /// Write plain text (`P3`) instead of binary (`P6`) PPM files
+    pub plain_ppm: bool,
+    /// Maps colors into the displayable range for the integer formats, the
+    /// float formats store the linear colors as they are
+    pub tone_mapper: ToneMapper,
+    /// Color space of the integer formats, float formats are always linear sRGB
+    pub color_space: ColorSpace,
+    /// Dither 8 bit images to hide banding in smooth gradients
+    pub dither: bool,
+}
+
+impl EncodeOptions {
+    /// Returns the options for images holding data rather than colors, such
+    /// as output passes, which are written without tone mapping.
+    pub fn raw(&self) -> EncodeOptions {
+        EncodeOptions {
+            tone_mapper: ToneMapper::default(),
+            dither: false,
+            ..self.clone()
+        }
+    }
+}
+
+/// Turns linear colors into the integers stored by the PPM and PNG encoders
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Quantizer {
+    pub tone_mapper: ToneMapper,
+    pub color_space: ColorSpace,
+    pub dither: bool,
+}
+
+impl Quantizer {
+    /// Returns the channels of pixel number `index` as integers in `0..=max`.
+    pub fn quantize(&self, color: Color, index: usize, max: u16) -> [u16; 3] {
+        let encoded = self.color_space.encode(self.tone_mapper.apply(color)).0;
+        let max = max as Real;
+        let mut channels = [encoded.x, encoded.y, encoded.z];
+        if self.dither {
+            // Triangular noise of up to one step, the same for every run
+            for (channel, value) in channels.iter_mut().enumerate() {
+                let hash = mix64(((index * 3 + channel) as u64).wrapping_add(GOLDEN_GAMMA));
+                let (a, b) = (hash >> 40, (hash >> 16) & 0xff_ffff);
+                let noise = (a + b) as Real / (1 << 24) as Real - 1.0;
+                *value = (*value * max + 0.5 + noise).floor();
+            }
+        } else {
+            channels = channels.map(|c| (c * (max + 0.999)).floor());
+        }
+        channels.map(|c| c.clamp(0.0, max) as u16)
+    }
+}
+
+/// Image file formats, detected from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    /// Portable Float Map, linear 32 bit floats
+    Pfm,
+    /// OpenEXR, linear half or full floats
+    Exr,
+}
+
+impl ImageFormat {
+    /// Returns the bit depths the format can store, the first being the default
+    pub fn bit_depths(self) -> &'static [BitDepth] {
+        match self {
+            ImageFormat::Ppm | ImageFormat::Png => &[BitDepth::Eight, BitDepth::Sixteen],
+            ImageFormat::Pfm => &[BitDepth::ThirtyTwo],
+            ImageFormat::Exr => &[BitDepth::ThirtyTwo, BitDepth::Sixteen],
+        }
+    }
+
+    /// Returns the requested bit depth, or the format's default when unset
+    pub fn bit_depth(self, requested: Option<BitDepth>) -> Result<BitDepth> {
+        let supported = self.bit_depths();
+        match requested {
+            None => Ok(supported[0]),
+            Some(bit_depth) if supported.contains(&bit_depth) => Ok(bit_depth),
+            Some(bit_depth) => Err(format!(
+                "{:?} output does not support {} bits per channel",
+                self,
+                bit_depth.bits()
+            )
+            .into()),
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("ppm") => Ok(ImageFormat::Ppm),
+            Some("png") => Ok(ImageFormat::Png),
+            Some("pfm") => Ok(ImageFormat::Pfm),
+            Some("exr") => Ok(ImageFormat::Exr),
+            _ => Err(format!(
+                "Unsupported output format for '{}', expected .ppm, .png, .pfm or .exr",
+                path.display()
+            )
+            .into()),
+        }
+    }
+}
+
+/// Encodes the image into a newly created file at `path`.
+pub fn write_file<E: ImageEncoder>(
+    image: &Framebuffer,
+    path: impl AsRef<Path>,
+    encoder: &E,
+) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    encoder.encode(image, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Saves the image to `path`, choosing the encoder from the file extension.
+pub fn save(image: &Framebuffer, path: impl AsRef<Path>, options: &EncodeOptions) -> Result<()> {
+    let path = path.as_ref();
+    let format = ImageFormat::from_path(path)?;
+    let bit_depth = format.bit_depth(options.bit_depth)?;
+    let quantizer = Quantizer {
+        tone_mapper: options.tone_mapper,
+        color_space: options.color_space,
+        // Sixteen bit steps are too fine to band.
+        dither: options.dither && bit_depth == BitDepth::Eight,
+    };
+    match format {
+        ImageFormat::Ppm => write_file(
+            image,
+            path,
+            &PpmEncoder {
+                bit_depth,
+                plain: options.plain_ppm,
+                quantizer,
+            },
+        ),
+        ImageFormat::Png => write_file(
+            image,
+            path,
+            &PngEncoder {
+                bit_depth,
+                quantizer,
+            },
+        ),
+        ImageFormat::Pfm => write_file(image, path, &PfmEncoder),
+        ImageFormat::Exr => write_file(image, path, &ExrEncoder { bit_depth }),
+    }
+}
+
+pub mod exr;
+pub mod pfm;
+pub mod png;
+pub mod ppm;
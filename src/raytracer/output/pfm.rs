@@ -0,0 +1,26 @@
+use std::io::Write;
+
+use crate::{
+    Result,
+    raytracer::{framebuffer::Framebuffer, output::ImageEncoder},
+};
+
+/// Portable Float Map encoder, storing linear radiance as 32 bit floats
+pub struct PfmEncoder;
+
+impl ImageEncoder for PfmEncoder {
+    fn encode(&self, image: &Framebuffer, writer: &mut dyn Write) -> Result<()> {
+        // A negative scale marks the samples as little endian.
+        writeln!(writer, "PF\n{} {}\n-1.0", image.width(), image.height())?;
+
+        // Scanlines are stored bottom to top.
+        for row in image.rows().rev() {
+            for pixel in row {
+                for c in [pixel.0.x, pixel.0.y, pixel.0.z] {
+                    writer.write_all(&(c as f32).to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
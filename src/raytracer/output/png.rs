@@ -0,0 +1,64 @@
+use std::io::Write;
+
+use png::{ColorType, Encoder, ScaledFloat, SourceChromaticities, SrgbRenderingIntent};
+
+use crate::{
+    Result,
+    raytracer::{
+        color_space::ColorSpace,
+        framebuffer::Framebuffer,
+        output::{BitDepth, ImageEncoder, Quantizer},
+    },
+};
+
+/// PNG encoder for 8 or 16 bit RGB images
+pub struct PngEncoder {
+    pub bit_depth: BitDepth,
+    pub quantizer: Quantizer,
+}
+
+impl ImageEncoder for PngEncoder {
+    fn encode(&self, image: &Framebuffer, writer: &mut dyn Write) -> Result<()> {
+        let mut encoder = Encoder::new(writer, image.width() as u32, image.height() as u32);
+        encoder.set_color(ColorType::Rgb);
+        match self.quantizer.color_space {
+            ColorSpace::Srgb => encoder.set_source_srgb(SrgbRenderingIntent::Perceptual),
+            color_space => {
+                let [white, red, green, blue] = color_space.chromaticities();
+                encoder
+                    .set_source_chromaticities(SourceChromaticities::new(white, red, green, blue));
+                encoder.set_source_gamma(ScaledFloat::new(1.0 / color_space.decoding_gamma()));
+            }
+        }
+
+        let max = self
+            .bit_depth
+            .max_value()
+            .ok_or("PNG supports 8 or 16 bits per channel")?;
+        let data: Vec<u8> = if self.bit_depth == BitDepth::Eight {
+            encoder.set_depth(png::BitDepth::Eight);
+            image
+                .pixels()
+                .iter()
+                .enumerate()
+                .flat_map(|(index, pixel)| {
+                    self.quantizer.quantize(*pixel, index, max).map(|c| c as u8)
+                })
+                .collect()
+        } else {
+            encoder.set_depth(png::BitDepth::Sixteen);
+            image
+                .pixels()
+                .iter()
+                .enumerate()
+                .flat_map(|(index, pixel)| self.quantizer.quantize(*pixel, index, max))
+                .flat_map(|c| c.to_be_bytes())
+                .collect()
+        };
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::io::Write;
+
+use crate::{
+    Result,
+    raytracer::{
+        framebuffer::Framebuffer,
+        output::{BitDepth, ImageEncoder, Quantizer},
+    },
+};
+
+/// Portable Pixmap encoder, writing either binary (`P6`) or plain text
+/// (`P3`) files
+pub struct PpmEncoder {
+    pub bit_depth: BitDepth,
+    pub plain: bool,
+    pub quantizer: Quantizer,
+}
+
+impl ImageEncoder for PpmEncoder {
+    fn encode(&self, image: &Framebuffer, writer: &mut dyn Write) -> Result<()> {
+        let max = self
+            .bit_depth
+            .max_value()
+            .ok_or("PPM supports 8 or 16 bits per channel")?;
+        let magic = if self.plain { "P3" } else { "P6" };
+        writeln!(
+            writer,
+            "{}\n{} {}\n{}",
+            magic,
+            image.width(),
+            image.height(),
+            max
+        )?;
+
+        for (index, pixel) in image.pixels().iter().enumerate() {
+            let [r, g, b] = self.quantizer.quantize(*pixel, index, max);
+            if self.plain {
+                writeln!(writer, "{} {} {}", r, g, b)?;
+            } else if self.bit_depth == BitDepth::Eight {
+                writer.write_all(&[r as u8, g as u8, b as u8])?;
+            } else {
+                // Samples wider than a byte are stored most significant byte first.
+                for c in [r, g, b] {
+                    writer.write_all(&c.to_be_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
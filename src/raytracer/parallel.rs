@@ -0,0 +1,25 @@
+use std::{sync::Mutex, thread};
+
+/// Calls `f` with the index and the pixels of every row, on `threads` worker
+/// threads. Workers pull rows from a shared queue until all are done, so
+/// rows that take longer do not hold up the others.
+pub fn for_each_row_parallel<'a, P, I, F>(threads: usize, rows: I, f: F)
+where
+    P: Send + 'a,
+    I: Iterator<Item = &'a mut [P]> + Send,
+    F: Fn(usize, &mut [P]) + Sync,
+{
+    let rows = Mutex::new(rows.enumerate());
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let Some((index, row)) = rows.lock().unwrap().next() else {
+                        break;
+                    };
+                    f(index, row);
+                }
+            });
+        }
+    });
+}
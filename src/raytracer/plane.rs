@@ -0,0 +1,62 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Plane through `point` extending forever, e.g. a ground or a wall. The
+/// texture coordinates are distances from `point` within the plane, so
+/// image textures repeat once per unit.
+pub struct InfinitePlane<T: Material> {
+    point: Point3,
+    normal: Vec3,
+    tangent: Vec3,
+    bitangent: Vec3,
+    mat: T,
+}
+
+impl<T: Material> InfinitePlane<T> {
+    pub fn new(point: Point3, normal: Vec3, material: T) -> Self {
+        let normal = normal.normalize();
+        let (tangent, bitangent) = normal.orthonormal_basis();
+        InfinitePlane {
+            point,
+            normal,
+            tangent,
+            bitangent,
+            mat: material,
+        }
+    }
+}
+
+impl<T: Material> Hitable for InfinitePlane<T> {
+    fn bounding_box(&self) -> Aabb {
+        Aabb::infinite()
+    }
+
+    /// The plane bounds the half space behind it.
+    fn contains(&self, p: Point3) -> Option<bool> {
+        Some((p - self.point).dot(self.normal) < 0.0)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let denom = self.normal.dot(ray.direction);
+        // The ray is parallel to the plane.
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = self.normal.dot(self.point - ray.origin) / denom;
+        if !range.contains(&t) {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let offset = p - self.point;
+        let (u, v) = (offset.dot(self.tangent), offset.dot(self.bitangent));
+        Some(HitRecord::new(p, self.normal, t, &self.mat).with_uv(u, v))
+    }
+}
@@ -0,0 +1,69 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Parallelogram with corner `q` spanned by the edges `u` and `v`. Its
+/// outward side is the one `u x v` points to, the texture coordinates run
+/// from 0 to 1 along the edges.
+pub struct Quad<T: Material> {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    /// Distance of the plane from the origin along the normal
+    d: Real,
+    /// `u x v` divided by its squared length, projects hit points onto the
+    /// edges
+    w: Vec3,
+    mat: T,
+}
+
+impl<T: Material> Quad<T> {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, material: T) -> Self {
+        let n = u.cross(v);
+        let normal = n.normalize();
+        Quad {
+            q,
+            u,
+            v,
+            normal,
+            d: normal.dot(q),
+            w: n / n.length_squared(),
+            mat: material,
+        }
+    }
+}
+
+impl<T: Material> Hitable for Quad<T> {
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(self.q, self.q + self.u + self.v)
+            .union(Aabb::from_points(self.q + self.u, self.q + self.v))
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let denom = self.normal.dot(ray.direction);
+        // The ray is parallel to the quad.
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = (self.d - self.normal.dot(ray.origin)) / denom;
+        if !range.contains(&t) {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let planar = p - self.q;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+        Some(HitRecord::new(p, self.normal, t, &self.mat).with_uv(alpha, beta))
+    }
+}
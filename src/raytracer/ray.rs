@@ -1,4 +1,5 @@
 use crate::raytracer::{
+    background::Background,
     color::Color,
     hitable::Hitable,
     vec3::{Point3, Real, Vec3},
@@ -7,11 +8,23 @@ use crate::raytracer::{
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
+    /// Moment the ray was sent, moving objects are hit where they were then
+    pub time: Real,
 }
 
 impl Ray {
     pub fn new(origin: Point3, direction: Vec3) -> Self {
-        Ray { origin, direction }
+        Ray::with_time(origin, direction, 0.0)
+    }
+
+    /// Creates a ray sent at `time`. Rays scattered off a surface keep the
+    /// time of the incoming ray.
+    pub fn with_time(origin: Point3, direction: Vec3, time: Real) -> Self {
+        Ray {
+            origin,
+            direction,
+            time,
+        }
     }
 
     pub fn origin(&self) -> Point3 {
@@ -22,30 +35,36 @@ impl Ray {
         self.direction
     }
 
+    pub fn time(&self) -> Real {
+        self.time
+    }
+
     pub fn at(&self, t: Real) -> Point3 {
         self.origin + self.direction * t
     }
 
-    pub fn color<T: Hitable>(&self, depth: usize, hitable: &T) -> Color {
+    pub fn color<T: Hitable>(
+        &self,
+        depth: usize,
+        hitable: &T,
+        background: &dyn Background,
+    ) -> Color {
         // If we've exceeded the ray bounce limit, no more light is gathered.
-        if depth <= 0 {
+        if depth == 0 {
             return Color::black();
         }
 
         if let Some(hit) = hitable.hit(self, &(1e-12..Real::INFINITY)) {
+            let emitted = hit.mat.emitted(&hit);
             if let Some((scattered_ray, attenuation)) = hit.mat.scatter(self, &hit) {
                 // Recursively calculate the color of the scattered ray.
-                let new_color = scattered_ray.color(depth - 1, hitable);
-                return attenuation * new_color;
+                let new_color = scattered_ray.color(depth - 1, hitable, background);
+                return emitted + attenuation * new_color;
             } else {
-                return Color::black(); // Ray was absorbed
+                return emitted; // Ray was absorbed
             }
         }
 
-        let unit_direction = self.direction.normalize();
-        let blue = Color::new(0.5, 0.7, 1.0);
-        let white = Color::new(1.0, 1.0, 1.0);
-        let t = 0.5 * (unit_direction.y + 1.0);
-        white.lerp(blue, t)
+        background.color(self)
     }
 }
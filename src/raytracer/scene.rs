@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::raytracer::{
+    background::Background,
+    hitable_list::HitableList,
+    medium::Fog,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Camera settings a scene was designed for. Unset values keep the render
+/// options' defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneCamera {
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub vertical_fov: Option<Real>,
+    #[serde(rename = "samples")]
+    pub samples_per_pixel: Option<usize>,
+    pub max_depth: Option<usize>,
+    #[serde(rename = "look_from")]
+    pub lookfrom: Option<Point3>,
+    #[serde(rename = "look_at")]
+    pub lookat: Option<Point3>,
+    pub vup: Option<Vec3>,
+    pub focus_distance: Option<Real>,
+    pub defocus_angle: Option<Real>,
+    pub shutter_open: Option<Real>,
+    pub shutter_close: Option<Real>,
+}
+
+/// A world to render together with its camera settings.
+pub struct Scene {
+    pub world: HitableList<'static>,
+    pub camera: SceneCamera,
+    /// Uses the render options' background when unset
+    pub background: Option<Box<dyn Background>>,
+    /// Fog filling the whole world, clear air when unset
+    pub fog: Option<Fog>,
+    /// Files the scene was read from, empty for built-in scenes
+    pub files: Vec<PathBuf>,
+}
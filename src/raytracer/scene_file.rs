@@ -0,0 +1,887 @@
+//! Declarative scene descriptions in TOML.
+//!
+//! ```toml
+//! [camera]
+//! look_from = [13, 2, 3]
+//! samples = 50
+//!
+//! [materials.ground]
+//! type = "lambertian"
+//! albedo = [0.5, 0.5, 0.5]
+//!
+//! [[objects]]
+//! type = "sphere"
+//! center = [0, -1000, 0]
+//! radius = 1000
+//! material = "ground"
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::{
+    Result,
+    raytracer::{
+        background::{
+            Background, EnvironmentMap, GradientBackground, ProceduralSky, SolidBackground,
+        },
+        bvh::Bvh,
+        capsule::Capsule,
+        color::Color,
+        cone::Cone,
+        csg::{Csg, CsgOperation},
+        cuboid::Cuboid,
+        cylinder::Cylinder,
+        disk::Disk,
+        hitable::Hitable,
+        hitable_list::HitableList,
+        input::load_image,
+        instance::Instance,
+        materials::{
+            Material, dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
+            metal::Metal,
+        },
+        matrix::Matrix4,
+        medium::{ConstantMedium, Fog},
+        obj::load_obj,
+        plane::InfinitePlane,
+        quad::Quad,
+        scene::{Scene, SceneCamera},
+        sphere::{MovingSphere, Sphere},
+        textures::{
+            Texture, checker::CheckerTexture, image::ImageTexture, solid_color::SolidColor,
+        },
+        torus::Torus,
+        triangle::Triangle,
+        vec3::{Point3, Real, Vec3},
+    },
+};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default)]
+    camera: SceneCamera,
+    background: Option<Spanned<BackgroundDescription>>,
+    fog: Option<Spanned<FogDescription>>,
+    #[serde(default)]
+    textures: HashMap<String, Spanned<TextureDescription>>,
+    #[serde(default)]
+    materials: HashMap<String, Spanned<MaterialDescription>>,
+    #[serde(default)]
+    // Kept as raw tables so errors can point at the object they occur in.
+    objects: Vec<Spanned<toml::Table>>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum BackgroundDescription {
+    Solid {
+        color: Color,
+    },
+    Gradient {
+        bottom: Color,
+        top: Color,
+    },
+    /// Equirectangular .hdr or .pfm image, relative to the scene file
+    Image {
+        file: String,
+        #[serde(default = "default_intensity")]
+        intensity: Real,
+    },
+    Sky {
+        sun_direction: Vec3,
+        sun_color: Option<Color>,
+    },
+}
+
+fn default_intensity() -> Real {
+    1.0
+}
+
+impl BackgroundDescription {
+    fn build(self, directory: &Path, files: &mut Vec<PathBuf>) -> Result<Box<dyn Background>> {
+        Ok(match self {
+            BackgroundDescription::Solid { color } => Box::new(SolidBackground::new(color)),
+            BackgroundDescription::Gradient { bottom, top } => {
+                Box::new(GradientBackground::new(bottom, top))
+            }
+            BackgroundDescription::Image { file, intensity } => {
+                let path = directory.join(file);
+                let image = load_image(&path)?;
+                files.push(path);
+                Box::new(EnvironmentMap::new(image, intensity))
+            }
+            BackgroundDescription::Sky {
+                sun_direction,
+                sun_color,
+            } => {
+                let sky = ProceduralSky::new(sun_direction);
+                Box::new(match sun_color {
+                    Some(sun_color) => sky.with_sun_color(sun_color),
+                    None => sky,
+                })
+            }
+        })
+    }
+}
+
+/// Homogeneous fog filling the scene, or only the part below `height`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FogDescription {
+    density: Real,
+    height: Option<Real>,
+    #[serde(default = "Color::white")]
+    color: Color,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum TextureDescription {
+    Checker {
+        scale: Real,
+        even: Color,
+        odd: Color,
+    },
+    /// Image file relative to the scene file
+    Image { file: String },
+}
+
+impl TextureDescription {
+    fn build(&self, directory: &Path, files: &mut Vec<PathBuf>) -> Result<Arc<dyn Texture>> {
+        Ok(match self {
+            TextureDescription::Checker { scale, even, odd } => {
+                Arc::new(CheckerTexture::from_colors(*scale, *even, *odd))
+            }
+            TextureDescription::Image { file } => {
+                let path = directory.join(file);
+                let image = load_image(&path)?;
+                files.push(path);
+                Arc::new(ImageTexture::new(image))
+            }
+        })
+    }
+}
+
+/// A material's albedo, either a `albedo` color or the name of a `texture`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Albedo {
+    albedo: Option<Color>,
+    texture: Option<String>,
+}
+
+impl Albedo {
+    fn build(
+        &self,
+        textures: &HashMap<&str, Arc<dyn Texture>>,
+    ) -> std::result::Result<Arc<dyn Texture>, String> {
+        match (self.albedo, &self.texture) {
+            (Some(albedo), None) => Ok(Arc::new(SolidColor::new(albedo))),
+            (None, Some(name)) => textures
+                .get(name.as_str())
+                .cloned()
+                .ok_or_else(|| format!("Unknown texture '{}'", name)),
+            _ => Err("Expected either an albedo or a texture".to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum MaterialDescription {
+    Lambertian {
+        #[serde(flatten)]
+        albedo: Albedo,
+    },
+    Metal {
+        #[serde(flatten)]
+        albedo: Albedo,
+        #[serde(default)]
+        fuzziness: Real,
+    },
+    Dielectric {
+        refraction_index: Real,
+    },
+    DiffuseLight {
+        emit: Color,
+    },
+}
+
+impl MaterialDescription {
+    fn build(
+        &self,
+        textures: &HashMap<&str, Arc<dyn Texture>>,
+    ) -> std::result::Result<Arc<dyn Material>, String> {
+        Ok(match self {
+            MaterialDescription::Lambertian { albedo } => {
+                Arc::new(Lambertian::textured(albedo.build(textures)?))
+            }
+            MaterialDescription::Metal { albedo, fuzziness } => {
+                Arc::new(Metal::textured(albedo.build(textures)?, *fuzziness))
+            }
+            MaterialDescription::Dielectric { refraction_index } => {
+                Arc::new(Dielectric::new(*refraction_index))
+            }
+            MaterialDescription::DiffuseLight { emit } => Arc::new(DiffuseLight::new(*emit)),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectDescription {
+    Sphere {
+        center: Point3,
+        radius: Real,
+        material: String,
+    },
+    /// Sphere moving from `center` at `start_time` to `end_center` at
+    /// `end_time`, by default 0 and 1
+    MovingSphere {
+        center: Point3,
+        end_center: Point3,
+        #[serde(default)]
+        start_time: Real,
+        #[serde(default = "default_end_time")]
+        end_time: Real,
+        radius: Real,
+        material: String,
+    },
+    Triangle {
+        vertices: [Point3; 3],
+        material: String,
+    },
+    /// Parallelogram spanned by the edges `u` and `v` from `corner`
+    Quad {
+        corner: Point3,
+        u: Vec3,
+        v: Vec3,
+        material: String,
+    },
+    Disk {
+        center: Point3,
+        normal: Vec3,
+        radius: Real,
+        material: String,
+    },
+    /// Infinite plane through `point`
+    Plane {
+        point: Point3,
+        normal: Vec3,
+        material: String,
+    },
+    /// Axis-aligned box between two opposite corners
+    Cuboid {
+        min: Point3,
+        max: Point3,
+        material: String,
+    },
+    /// Cylinder around the segment from `base` to `top`
+    Cylinder {
+        base: Point3,
+        top: Point3,
+        radius: Real,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: String,
+    },
+    Cone {
+        base: Point3,
+        apex: Point3,
+        radius: Real,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: String,
+    },
+    /// Ring around `axis` through `center`
+    Torus {
+        center: Point3,
+        axis: Vec3,
+        major_radius: Real,
+        minor_radius: Real,
+        material: String,
+    },
+    /// Rounded cylinder around the segment from `start` to `end`
+    Capsule {
+        start: Point3,
+        end: Point3,
+        radius: Real,
+        material: String,
+    },
+    /// Boolean combination of two closed objects, given as inline tables
+    Csg {
+        operation: CsgOperation,
+        a: Box<ObjectDescription>,
+        b: Box<ObjectDescription>,
+    },
+    /// Smoke of constant density filling a closed `boundary` object, given
+    /// as an inline table. The boundary's material is not used.
+    Medium {
+        boundary: Box<ObjectDescription>,
+        density: Real,
+        #[serde(default = "Color::white")]
+        color: Color,
+    },
+    /// Any other object, given as an inline table, scaled, then rotated,
+    /// then translated. Meshes have these fields themselves.
+    Instance {
+        object: Box<ObjectDescription>,
+        /// Scale factor, the same for all axes or one per axis
+        scale: Option<Scale>,
+        /// Rotation in degrees around the x, y and z axes, in that order
+        rotate: Option<Vec3>,
+        translate: Option<Vec3>,
+    },
+    /// Wavefront OBJ file, relative to the scene file. Faces without a
+    /// material from the OBJ's own libraries use `material`. With a
+    /// transform, meshes of the same file share their geometry.
+    Mesh {
+        file: String,
+        material: Option<String>,
+        /// Scale factor, the same for all axes or one per axis
+        scale: Option<Scale>,
+        /// Rotation in degrees around the x, y and z axes, in that order
+        rotate: Option<Vec3>,
+        translate: Option<Vec3>,
+    },
+}
+
+impl ObjectDescription {
+    /// Builds any object but a mesh, which may expand to several objects.
+    fn build(
+        self,
+        materials: &HashMap<&str, Arc<dyn Material>>,
+    ) -> std::result::Result<Box<dyn Hitable>, String> {
+        self.check_dimensions()?;
+        Ok(match self {
+            ObjectDescription::Sphere {
+                center,
+                radius,
+                material: name,
+            } => Box::new(Sphere::new(
+                center,
+                radius,
+                find_material(materials, &name)?,
+            )),
+            ObjectDescription::MovingSphere {
+                start_time,
+                end_time,
+                ..
+            } if end_time <= start_time => {
+                return Err(
+                    "The end time of a moving sphere must be after its start time".to_string(),
+                );
+            }
+            ObjectDescription::MovingSphere {
+                center,
+                end_center,
+                start_time,
+                end_time,
+                radius,
+                material: name,
+            } => Box::new(MovingSphere::new(
+                center,
+                start_time,
+                end_center,
+                end_time,
+                radius,
+                find_material(materials, &name)?,
+            )),
+            ObjectDescription::Triangle {
+                vertices: [a, b, c],
+                material: name,
+            } => Box::new(Triangle::new(a, b, c, find_material(materials, &name)?)),
+            ObjectDescription::Quad {
+                corner,
+                u,
+                v,
+                material: name,
+            } => Box::new(Quad::new(corner, u, v, find_material(materials, &name)?)),
+            ObjectDescription::Disk {
+                center,
+                normal,
+                radius,
+                material: name,
+            } => Box::new(Disk::new(
+                center,
+                normal,
+                radius,
+                find_material(materials, &name)?,
+            )),
+            ObjectDescription::Plane {
+                point,
+                normal,
+                material: name,
+            } => Box::new(InfinitePlane::new(
+                point,
+                normal,
+                find_material(materials, &name)?,
+            )),
+            ObjectDescription::Cuboid {
+                min,
+                max,
+                material: name,
+            } => Box::new(Cuboid::new(min, max, find_material(materials, &name)?)),
+            ObjectDescription::Cylinder {
+                base,
+                top,
+                radius,
+                capped,
+                material: name,
+            } => {
+                let cylinder = Cylinder::new(base, top, radius, find_material(materials, &name)?);
+                if capped {
+                    Box::new(cylinder)
+                } else {
+                    Box::new(cylinder.without_caps())
+                }
+            }
+            ObjectDescription::Cone {
+                base,
+                apex,
+                radius,
+                capped,
+                material: name,
+            } => {
+                let cone = Cone::new(base, apex, radius, find_material(materials, &name)?);
+                if capped {
+                    Box::new(cone)
+                } else {
+                    Box::new(cone.without_cap())
+                }
+            }
+            ObjectDescription::Torus {
+                center,
+                axis,
+                major_radius,
+                minor_radius,
+                material: name,
+            } => Box::new(Torus::new(
+                center,
+                axis,
+                major_radius,
+                minor_radius,
+                find_material(materials, &name)?,
+            )),
+            ObjectDescription::Capsule {
+                start,
+                end,
+                radius,
+                material: name,
+            } => Box::new(Capsule::new(
+                start,
+                end,
+                radius,
+                find_material(materials, &name)?,
+            )),
+            ObjectDescription::Csg { operation, a, b } => Box::new(Csg::new(
+                operation,
+                a.build_solid(materials)?,
+                b.build_solid(materials)?,
+            )),
+            ObjectDescription::Medium { density, .. } if density <= 0.0 => {
+                return Err("The medium density must be positive".to_string());
+            }
+            ObjectDescription::Medium {
+                boundary,
+                density,
+                color,
+            } => Box::new(ConstantMedium::new(
+                boundary.build_solid(materials)?,
+                density,
+                color,
+            )),
+            ObjectDescription::Instance {
+                object,
+                scale,
+                rotate,
+                translate,
+            } => Box::new(
+                Instance::new(
+                    Arc::from(object.build(materials)?),
+                    transform(scale, rotate, translate),
+                )
+                .map_err(|err| err.to_string())?,
+            ),
+            ObjectDescription::Mesh { .. } => {
+                return Err("Meshes cannot be nested in other objects".to_string());
+            }
+        })
+    }
+
+    /// Checks the sizes of shapes that would have no volume or a broken
+    /// bounding box otherwise.
+    fn check_dimensions(&self) -> std::result::Result<(), String> {
+        let error = match self {
+            ObjectDescription::Cylinder { radius, .. } if *radius <= 0.0 => {
+                "The radius of a cylinder must be positive"
+            }
+            ObjectDescription::Cone { radius, .. } if *radius <= 0.0 => {
+                "The radius of a cone must be positive"
+            }
+            ObjectDescription::Capsule { radius, .. } if *radius <= 0.0 => {
+                "The radius of a capsule must be positive"
+            }
+            ObjectDescription::Cylinder { base, top, .. } if (*top - *base).near_zero() => {
+                "The base and the top of a cylinder must be apart"
+            }
+            ObjectDescription::Cone { base, apex, .. } if (*apex - *base).near_zero() => {
+                "The base and the apex of a cone must be apart"
+            }
+            ObjectDescription::Torus { axis, .. } if axis.near_zero() => {
+                "The axis of a torus must not be zero"
+            }
+            ObjectDescription::Torus {
+                major_radius,
+                minor_radius,
+                ..
+            } if *minor_radius <= 0.0 || *major_radius <= *minor_radius => {
+                "The radii of a torus must be positive, the minor one smaller"
+            }
+            _ => return Ok(()),
+        };
+        Err(error.to_string())
+    }
+
+    /// Builds an operand of a CSG object or the boundary of a medium. Rays
+    /// tell inside from outside by the surfaces they cross, so these must be
+    /// closed, or an infinite plane bounding a half space.
+    fn build_solid(
+        self,
+        materials: &HashMap<&str, Arc<dyn Material>>,
+    ) -> std::result::Result<Box<dyn Hitable>, String> {
+        match self.not_solid() {
+            Some(reason) => Err(format!("{} and cannot bound a solid", reason)),
+            None => self.build(materials),
+        }
+    }
+
+    /// Returns why the object cannot bound a solid, if it cannot.
+    fn not_solid(&self) -> Option<&'static str> {
+        match self {
+            ObjectDescription::Triangle { .. } => Some("A triangle is not closed"),
+            ObjectDescription::Quad { .. } => Some("A quad is not closed"),
+            ObjectDescription::Disk { .. } => Some("A disk is not closed"),
+            ObjectDescription::Cylinder { capped: false, .. } => {
+                Some("An uncapped cylinder is not closed")
+            }
+            ObjectDescription::Cone { capped: false, .. } => Some("An uncapped cone is not closed"),
+            // Rays scatter inside media at random, they have no surface.
+            ObjectDescription::Medium { .. } => Some("A medium has no surface"),
+            ObjectDescription::Instance { object, .. } => object.not_solid(),
+            _ => None,
+        }
+    }
+}
+
+/// Looks up a material by the name an object refers to it with.
+fn find_material(
+    materials: &HashMap<&str, Arc<dyn Material>>,
+    name: &str,
+) -> std::result::Result<Arc<dyn Material>, String> {
+    materials
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("Unknown material '{}'", name))
+}
+
+fn default_capped() -> bool {
+    true
+}
+
+fn default_end_time() -> Real {
+    1.0
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scale {
+    Uniform(Real),
+    PerAxis(Vec3),
+}
+
+/// Returns the transform that scales, then rotates, then translates.
+fn transform(scale: Option<Scale>, rotate: Option<Vec3>, translate: Option<Vec3>) -> Matrix4 {
+    let scale = match scale {
+        Some(Scale::Uniform(factor)) => Vec3::new(factor, factor, factor),
+        Some(Scale::PerAxis(factors)) => factors,
+        None => Vec3::new(1.0, 1.0, 1.0),
+    };
+    let rotate = rotate.unwrap_or(Vec3::zero());
+    Matrix4::translation(translate.unwrap_or(Vec3::zero()))
+        * Matrix4::rotation_z(rotate.z)
+        * Matrix4::rotation_y(rotate.y)
+        * Matrix4::rotation_x(rotate.x)
+        * Matrix4::scaling(scale)
+}
+
+/// Loads a scene from a TOML file.
+pub fn load_scene(path: impl AsRef<Path>) -> Result<Scene> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+    parse_scene(&source, path)
+}
+
+/// Builds the scene described by `source`, the contents of the file at
+/// `path`. Relative paths in the scene are relative to that file.
+fn parse_scene(source: &str, path: &Path) -> Result<Scene> {
+    // Parse errors already describe the offending line and column.
+    let file: SceneFile =
+        toml::from_str(source).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    if let (Some(open), Some(close)) = (file.camera.shutter_open, file.camera.shutter_close)
+        && close < open
+    {
+        return Err(format!(
+            "{}: The shutter closes at {} before it opens at {}",
+            path.display(),
+            close,
+            open
+        )
+        .into());
+    }
+
+    // Errors found in tables point to the line the table starts on.
+    let fail = |span: std::ops::Range<usize>, message: String| {
+        let line = source[..span.start].matches('\n').count() + 1;
+        format!("{}:{}: {}", path.display(), line, message)
+    };
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut files = vec![path.to_path_buf()];
+    let textures = file
+        .textures
+        .iter()
+        .map(|(name, description)| {
+            let texture = description
+                .get_ref()
+                .build(directory, &mut files)
+                .map_err(|err| {
+                    fail(
+                        description.span(),
+                        format!("Invalid texture '{}': {}", name, err),
+                    )
+                })?;
+            Ok((name.as_str(), texture))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+    let materials = file
+        .materials
+        .iter()
+        .map(|(name, description)| {
+            let material = description.get_ref().build(&textures).map_err(|err| {
+                fail(
+                    description.span(),
+                    format!("Invalid material '{}': {}", name, err),
+                )
+            })?;
+            Ok((name.as_str(), material))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let mut world = HitableList::new();
+    let mut shared_meshes: HashMap<(String, Option<String>), Arc<dyn Hitable>> = HashMap::new();
+    for object in file.objects {
+        let span = object.span();
+        let object = ObjectDescription::deserialize(object.into_inner())
+            .map_err(|err| fail(span.clone(), format!("Invalid object: {}", err.message())))?;
+        match object {
+            ObjectDescription::Mesh {
+                file,
+                material: name,
+                scale,
+                rotate,
+                translate,
+            } => {
+                let mut load = |name: &Option<String>| {
+                    let default_material = match name {
+                        Some(name) => find_material(&materials, name)
+                            .map_err(|err| fail(span.clone(), err))?,
+                        None => Arc::new(Lambertian::new(Color::new(0.8, 0.8, 0.8))),
+                    };
+                    load_obj(directory.join(&file), default_material, &mut files)
+                        .map_err(|err| fail(span.clone(), format!("Failed to load mesh: {}", err)))
+                };
+
+                if scale.is_none() && rotate.is_none() && translate.is_none() {
+                    for mesh in load(&name)? {
+                        world.add(mesh);
+                    }
+                    continue;
+                }
+
+                // Every placement of a file refers to the same geometry.
+                let key = (file.clone(), name.clone());
+                let object = match shared_meshes.get(&key) {
+                    Some(object) => Arc::clone(object),
+                    None => {
+                        let mut meshes = HitableList::new();
+                        for mesh in load(&name)? {
+                            meshes.add(mesh);
+                        }
+                        let object: Arc<dyn Hitable> = Arc::new(Bvh::new(meshes));
+                        shared_meshes.insert(key, Arc::clone(&object));
+                        object
+                    }
+                };
+                let instance = Instance::new(object, transform(scale, rotate, translate))
+                    .map_err(|err| fail(span, err.to_string()))?;
+                world.add(instance);
+            }
+            object => world.add(object.build(&materials).map_err(|err| fail(span, err))?),
+        }
+    }
+
+    let background = file
+        .background
+        .map(|background| {
+            let span = background.span();
+            background
+                .into_inner()
+                .build(directory, &mut files)
+                .map_err(|err| fail(span, format!("Invalid background: {}", err)))
+        })
+        .transpose()?;
+
+    let fog = match file.fog {
+        Some(fog) if fog.get_ref().density <= 0.0 => {
+            return Err(fail(fog.span(), "The fog density must be positive".to_string()).into());
+        }
+        Some(fog) => {
+            let fog = fog.into_inner();
+            let fill = Fog::new(fog.density, fog.color);
+            Some(match fog.height {
+                Some(height) => fill.below(height),
+                None => fill,
+            })
+        }
+        None => None,
+    };
+
+    Ok(Scene {
+        world,
+        camera: file.camera,
+        background,
+        fog,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATERIALS: &str = r#"
+[materials.white]
+type = "lambertian"
+albedo = [0.8, 0.8, 0.8]
+"#;
+
+    /// Loads a scene made of the materials above and `objects`, returning
+    /// the error message if it fails.
+    fn load(objects: &str) -> std::result::Result<Scene, String> {
+        parse_scene(&format!("{}{}", MATERIALS, objects), Path::new("test.toml"))
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_textures_and_materials() {
+        let texture = "[textures.wood]\ntype = \"image\"\nfile = \"missing.png\"\n";
+        let err = load(texture)
+            .err()
+            .expect("the missing image should be rejected");
+        assert!(
+            err.starts_with("test.toml:5: Invalid texture 'wood': "),
+            "{}",
+            err
+        );
+
+        let material = "[materials.red]\ntype = \"lambertian\"\ntexture = \"wood\"\n";
+        let err = load(material)
+            .err()
+            .expect("the unknown texture should be rejected");
+        assert!(
+            err.starts_with("test.toml:5: Invalid material 'red': "),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn rejects_a_shutter_closing_before_it_opens() {
+        let camera = "[camera]\nshutter_open = 1\nshutter_close = 0\n";
+        assert!(load(camera).is_err());
+    }
+
+    #[test]
+    fn rejects_shapes_without_volume() {
+        let objects = [
+            r#"type = "cylinder"
+base = [0, 0, 0]
+top = [0, 0, 0]
+radius = 1"#,
+            r#"type = "cone"
+base = [0, 0, 0]
+apex = [0, 1, 0]
+radius = 0"#,
+            r#"type = "torus"
+center = [0, 0, 0]
+axis = [0, 0, 0]
+major_radius = 1
+minor_radius = 0.25"#,
+            r#"type = "torus"
+center = [0, 0, 0]
+axis = [0, 1, 0]
+major_radius = 1
+minor_radius = 2"#,
+        ];
+        for object in objects {
+            let scene = format!("[[objects]]\n{}\nmaterial = \"white\"\n", object);
+            let err = load(&scene).err().expect("the object should be rejected");
+            assert!(err.starts_with("test.toml:5: "), "{}", err);
+        }
+    }
+
+    #[test]
+    fn rejects_media_as_solids() {
+        let sphere = r#"{ type = "sphere", center = [0, 0, 0], radius = 1, material = "white" }"#;
+        let medium = format!(
+            r#"{{ type = "medium", density = 1, boundary = {} }}"#,
+            sphere
+        );
+
+        let csg = format!(
+            "[[objects]]\ntype = \"csg\"\noperation = \"union\"\na = {}\nb = {}\n",
+            sphere, medium
+        );
+        let err = load(&csg)
+            .err()
+            .expect("a medium operand should be rejected");
+        assert_eq!(
+            err,
+            "test.toml:5: A medium has no surface and cannot bound a solid"
+        );
+
+        let nested = format!(
+            "[[objects]]\ntype = \"medium\"\ndensity = 1\nboundary = {}\n",
+            medium
+        );
+        assert!(load(&nested).is_err());
+
+        let valid = format!(
+            "[[objects]]\ntype = \"medium\"\ndensity = 1\nboundary = {}\n",
+            sphere
+        );
+        assert!(load(&valid).is_ok());
+    }
+}
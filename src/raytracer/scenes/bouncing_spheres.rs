@@ -0,0 +1,37 @@
+use crate::raytracer::{
+    color::Color,
+    hitable_list::HitableList,
+    materials::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+    scene::{Scene, SceneCamera},
+    scenes::final_scene::{add_sphere_field, field_camera},
+    sphere::Sphere,
+    vec3::Point3,
+};
+
+/// The final scene of the first book with the small diffuse spheres bouncing
+/// up while the shutter is open, blurring them.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+    add_sphere_field(&mut world, Some(0.5));
+
+    let material1 = Dielectric::new(1.5);
+    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, material1));
+
+    let material2 = Lambertian::new(Color::new(0.4, 0.2, 0.1));
+    world.add(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, material2));
+
+    let material3 = Metal::new(Color::new(0.7, 0.6, 0.5), 0.0);
+    world.add(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3));
+
+    Scene {
+        world,
+        camera: SceneCamera {
+            shutter_open: Some(0.0),
+            shutter_close: Some(1.0),
+            ..field_camera()
+        },
+        background: None,
+        fog: None,
+        files: Vec::new(),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::raytracer::{
+    color::Color,
+    hitable_list::HitableList,
+    materials::lambertian::Lambertian,
+    scene::{Scene, SceneCamera},
+    sphere::Sphere,
+    textures::checker::CheckerTexture,
+    vec3::Point3,
+};
+
+/// Two large spheres sharing a solid checker texture.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+
+    let checker =
+        || CheckerTexture::from_colors(0.32, Color::new(0.2, 0.3, 0.1), Color::new(0.9, 0.9, 0.9));
+    world.add(Sphere::new(
+        Point3::new(0.0, -10.0, 0.0),
+        10.0,
+        Lambertian::textured(checker()),
+    ));
+    world.add(Sphere::new(
+        Point3::new(0.0, 10.0, 0.0),
+        10.0,
+        Lambertian::textured(checker()),
+    ));
+
+    Scene {
+        world,
+        camera: SceneCamera {
+            lookfrom: Some(Point3::new(13.0, 2.0, 3.0)),
+            lookat: Some(Point3::new(0.0, 0.0, 0.0)),
+            vertical_fov: Some(20.0),
+            defocus_angle: Some(0.0),
+            ..SceneCamera::default()
+        },
+        background: None,
+        fog: None,
+        files: Vec::new(),
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use crate::raytracer::{
+    background::SolidBackground,
+    color::Color,
+    cuboid::Cuboid,
+    hitable_list::HitableList,
+    instance::Instance,
+    materials::{Material, diffuse_light::DiffuseLight, lambertian::Lambertian},
+    matrix::Matrix4,
+    quad::Quad,
+    scene::{Scene, SceneCamera},
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Adds the red and green side walls and the white floor, ceiling and back
+/// wall of a box 555 units wide, open towards -z.
+pub(super) fn add_walls(world: &mut HitableList, white: &Arc<dyn Material>) {
+    let red: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.65, 0.05, 0.05)));
+    let green: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.12, 0.45, 0.15)));
+
+    world.add(Quad::new(
+        Point3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        green,
+    ));
+    world.add(Quad::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        red,
+    ));
+    world.add(Quad::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        white.clone(),
+    ));
+    world.add(Quad::new(
+        Point3::new(555.0, 555.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        white.clone(),
+    ));
+    world.add(Quad::new(
+        Point3::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        white.clone(),
+    ));
+}
+
+/// Returns the tall and the short box standing in the box, turned towards
+/// the walls.
+pub(super) fn boxes(material: &Arc<dyn Material>) -> [Instance; 2] {
+    // The boxes are built at the origin, then turned and moved into place.
+    let place = |size: Vec3, angle: Real, position: Vec3| {
+        let cuboid = Cuboid::new(Point3::zero(), size, material.clone());
+        let transform = Matrix4::translation(position) * Matrix4::rotation_y(angle);
+        Instance::new(Arc::new(cuboid), transform).expect("rotations are invertible")
+    };
+    [
+        place(
+            Vec3::new(165.0, 330.0, 165.0),
+            15.0,
+            Vec3::new(265.0, 0.0, 295.0),
+        ),
+        place(
+            Vec3::new(165.0, 165.0, 165.0),
+            -18.0,
+            Vec3::new(130.0, 0.0, 65.0),
+        ),
+    ]
+}
+
+/// The camera looking into the open side of the box
+pub(super) fn camera() -> SceneCamera {
+    SceneCamera {
+        width: Some(600),
+        height: Some(600),
+        samples_per_pixel: Some(200),
+        lookfrom: Some(Point3::new(278.0, 278.0, -800.0)),
+        lookat: Some(Point3::new(278.0, 278.0, 0.0)),
+        vertical_fov: Some(40.0),
+        defocus_angle: Some(0.0),
+        ..SceneCamera::default()
+    }
+}
+
+/// The classic Cornell box, lit only by the area light in its ceiling.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+
+    let white: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(Color::new(15.0, 15.0, 15.0)));
+
+    add_walls(&mut world, &white);
+    world.add(Quad::new(
+        Point3::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        light,
+    ));
+
+    let [tall_box, short_box] = boxes(&white);
+    world.add(tall_box);
+    world.add(short_box);
+
+    Scene {
+        world,
+        camera: camera(),
+        background: Some(Box::new(SolidBackground::new(Color::black()))),
+        fog: None,
+        files: Vec::new(),
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use crate::raytracer::{
+    background::SolidBackground,
+    color::Color,
+    hitable_list::HitableList,
+    materials::{Material, diffuse_light::DiffuseLight, lambertian::Lambertian},
+    medium::ConstantMedium,
+    quad::Quad,
+    scene::Scene,
+    scenes::cornell_box::{add_walls, boxes, camera},
+    vec3::{Point3, Vec3},
+};
+
+/// The Cornell box with its boxes made of dark smoke and white mist, under
+/// a larger and dimmer light.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+
+    let white: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(Color::new(7.0, 7.0, 7.0)));
+
+    add_walls(&mut world, &white);
+    world.add(Quad::new(
+        Point3::new(113.0, 554.0, 127.0),
+        Vec3::new(330.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 305.0),
+        light,
+    ));
+
+    // Only the shape of the boundaries matters, not their material.
+    let [tall_box, short_box] = boxes(&white);
+    world.add(ConstantMedium::new(tall_box, 0.01, Color::black()));
+    world.add(ConstantMedium::new(short_box, 0.01, Color::white()));
+
+    Scene {
+        world,
+        camera: camera(),
+        background: Some(Box::new(SolidBackground::new(Color::black()))),
+        fog: None,
+        files: Vec::new(),
+    }
+}
@@ -0,0 +1,104 @@
+use crate::raytracer::{
+    color::Color,
+    hitable_list::HitableList,
+    materials::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+    scene::{Scene, SceneCamera},
+    sphere::{MovingSphere, Sphere},
+    vec3::{Point3, Real, Vec3},
+};
+
+/// The final scene of the book, a field of small random spheres around three
+/// large ones.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+    add_sphere_field(&mut world, None);
+
+    let material1a = Dielectric::new(1.5);
+    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, material1a));
+
+    let material1b = Dielectric::new(1.0 / 1.5);
+    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 0.8, material1b));
+
+    let material2 = Lambertian::new(Color::new(0.4, 0.2, 0.1));
+    world.add(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, material2));
+
+    let material3 = Metal::new(Color::new(0.7, 0.6, 0.5), 0.0);
+    world.add(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3));
+
+    Scene {
+        world,
+        camera: field_camera(),
+        background: None,
+        fog: None,
+        files: Vec::new(),
+    }
+}
+
+/// Adds the ground and the field of small random spheres, leaving room for
+/// the large ones. With a `bounce` height, the diffuse spheres move up by a
+/// random amount below it between time 0 and 1.
+pub(super) fn add_sphere_field(world: &mut HitableList, bounce: Option<Real>) {
+    let ground_material = Lambertian::new(Color::new(0.5, 0.5, 0.5));
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground_material,
+    ));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let random_vec = Vec3::random(0.0..1.0);
+            let choose_mat = random_vec.x;
+            let center = Point3::new(
+                a as Real + 0.9 * random_vec.y,
+                0.2,
+                b as Real + 0.9 * random_vec.z,
+            );
+
+            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
+                if choose_mat < 0.8 {
+                    // diffuse
+                    let albedo = Color::random() * Color::random();
+                    let sphere_material = Lambertian::new(albedo);
+                    match bounce {
+                        Some(height) => {
+                            let end_center =
+                                center + Vec3::new(0.0, Vec3::random(0.0..height).x, 0.0);
+                            world.add(MovingSphere::new(
+                                center,
+                                0.0,
+                                end_center,
+                                1.0,
+                                0.2,
+                                sphere_material,
+                            ));
+                        }
+                        None => world.add(Sphere::new(center, 0.2, sphere_material)),
+                    }
+                } else if choose_mat < 0.95 {
+                    // metal
+                    let albedo = Color::random() / 2.0 + 0.5;
+                    let fuzz = Vec3::random(0.0..0.5).x;
+                    let sphere_material = Metal::new(albedo, fuzz);
+                    world.add(Sphere::new(center, 0.2, sphere_material));
+                } else {
+                    // glass
+                    let sphere_material = Dielectric::new(1.5);
+                    world.add(Sphere::new(center, 0.2, sphere_material));
+                }
+            }
+        }
+    }
+}
+
+/// The camera looking over the sphere field at the large spheres
+pub(super) fn field_camera() -> SceneCamera {
+    SceneCamera {
+        lookfrom: Some(Point3::new(13.0, 2.0, 3.0)),
+        lookat: Some(Point3::new(0.0, 0.0, 0.0)),
+        vertical_fov: Some(20.0),
+        focus_distance: Some(10.0),
+        defocus_angle: Some(0.6),
+        ..SceneCamera::default()
+    }
+}
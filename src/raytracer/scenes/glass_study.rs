@@ -0,0 +1,59 @@
+use crate::raytracer::{
+    color::Color,
+    hitable_list::HitableList,
+    materials::{dielectric::Dielectric, lambertian::Lambertian},
+    scene::{Scene, SceneCamera},
+    sphere::Sphere,
+    textures::checker::CheckerTexture,
+    vec3::{Point3, Real},
+};
+
+/// A row of glass spheres with increasing refraction index in front of a
+/// hollow bubble, over a checkered floor that shows the distortion.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+
+    let floor = CheckerTexture::from_colors(0.5, Color::new(0.1, 0.1, 0.1), Color::white());
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::textured(floor),
+    ));
+
+    // Water, glass, sapphire, diamond and a high index crystal
+    for (i, refraction_index) in [1.33, 1.5, 1.77, 2.42, 3.0].into_iter().enumerate() {
+        let x = (i as Real - 2.0) * 1.1;
+        world.add(Sphere::new(
+            Point3::new(x, 0.5, 1.0),
+            0.5,
+            Dielectric::new(refraction_index),
+        ));
+    }
+
+    // A thick glass shell around an air bubble
+    world.add(Sphere::new(
+        Point3::new(0.0, 1.2, -1.5),
+        1.2,
+        Dielectric::new(1.5),
+    ));
+    world.add(Sphere::new(
+        Point3::new(0.0, 1.2, -1.5),
+        1.0,
+        Dielectric::new(1.0 / 1.5),
+    ));
+
+    Scene {
+        world,
+        camera: SceneCamera {
+            lookfrom: Some(Point3::new(0.0, 2.5, 8.0)),
+            lookat: Some(Point3::new(0.0, 0.8, 0.0)),
+            vertical_fov: Some(35.0),
+            focus_distance: Some(7.5),
+            defocus_angle: Some(0.3),
+            ..SceneCamera::default()
+        },
+        background: None,
+        fog: None,
+        files: Vec::new(),
+    }
+}
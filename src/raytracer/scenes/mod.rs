@@ -0,0 +1,68 @@
+//! Built-in scenes, selectable by name from the command line.
+
+use clap::builder::PossibleValuesParser;
+
+use crate::raytracer::scene::Scene;
+
+/// A named scene with a function that builds it.
+pub struct SceneEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub build: fn() -> Scene,
+}
+
+pub const SCENES: &[SceneEntry] = &[
+    SceneEntry {
+        name: "final",
+        description: "Random spheres around three large ones, the book's final scene",
+        build: final_scene::build,
+    },
+    SceneEntry {
+        name: "three-spheres",
+        description: "Diffuse, glass and metal spheres on a yellow ground",
+        build: three_spheres::build,
+    },
+    SceneEntry {
+        name: "cornell-box",
+        description: "The Cornell box lit by a single area light",
+        build: cornell_box::build,
+    },
+    SceneEntry {
+        name: "cornell-smoke",
+        description: "The Cornell box with boxes of smoke and mist",
+        build: cornell_smoke::build,
+    },
+    SceneEntry {
+        name: "checkered-spheres",
+        description: "Two large spheres with a checker texture",
+        build: checkered_spheres::build,
+    },
+    SceneEntry {
+        name: "glass-study",
+        description: "Glass spheres of increasing refraction index over a checkered floor",
+        build: glass_study::build,
+    },
+    SceneEntry {
+        name: "bouncing-spheres",
+        description: "The final scene with motion blurred bouncing spheres",
+        build: bouncing_spheres::build,
+    },
+];
+
+/// Returns the scene registered under `name`.
+pub fn find(name: &str) -> Option<&'static SceneEntry> {
+    SCENES.iter().find(|entry| entry.name == name)
+}
+
+/// Command line parser accepting only registered scene names.
+pub fn scene_name_parser() -> PossibleValuesParser {
+    PossibleValuesParser::new(SCENES.iter().map(|entry| entry.name))
+}
+
+pub mod bouncing_spheres;
+pub mod checkered_spheres;
+pub mod cornell_box;
+pub mod cornell_smoke;
+pub mod final_scene;
+pub mod glass_study;
+pub mod three_spheres;
@@ -0,0 +1,61 @@
+use crate::raytracer::{
+    color::Color,
+    hitable_list::HitableList,
+    materials::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+    scene::{Scene, SceneCamera},
+    sphere::Sphere,
+    vec3::Point3,
+};
+
+/// Diffuse, hollow glass and fuzzy metal spheres side by side on a yellow
+/// ground, the material test scene of the book.
+pub fn build() -> Scene {
+    let mut world = HitableList::new();
+
+    let material_ground = Lambertian::new(Color::new(0.8, 0.8, 0.0));
+    let material_center = Lambertian::new(Color::new(0.1, 0.2, 0.5));
+    let material_left = Dielectric::new(1.5);
+    let material_bubble = Dielectric::new(1.0 / 1.5);
+    let material_right = Metal::new(Color::new(0.8, 0.6, 0.2), 1.0);
+
+    world.add(Sphere::new(
+        Point3::new(0.0, -100.5, -1.0),
+        100.0,
+        material_ground,
+    ));
+    world.add(Sphere::new(
+        Point3::new(0.0, 0.0, -1.2),
+        0.5,
+        material_center,
+    ));
+    world.add(Sphere::new(
+        Point3::new(-1.0, 0.0, -1.0),
+        0.5,
+        material_left,
+    ));
+    world.add(Sphere::new(
+        Point3::new(-1.0, 0.0, -1.0),
+        0.4,
+        material_bubble,
+    ));
+    world.add(Sphere::new(
+        Point3::new(1.0, 0.0, -1.0),
+        0.5,
+        material_right,
+    ));
+
+    Scene {
+        world,
+        camera: SceneCamera {
+            lookfrom: Some(Point3::new(-2.0, 2.0, 1.0)),
+            lookat: Some(Point3::new(0.0, 0.0, -1.0)),
+            vertical_fov: Some(20.0),
+            focus_distance: Some(3.4),
+            defocus_angle: Some(10.0),
+            ..SceneCamera::default()
+        },
+        background: None,
+        fog: None,
+        files: Vec::new(),
+    }
+}
@@ -1,10 +1,11 @@
-use std::ops::Range;
+use std::{f64::consts::PI, ops::Range};
 
 use crate::raytracer::{
+    aabb::Aabb,
     hitable::{HitRecord, Hitable},
     materials::Material,
     ray::Ray,
-    vec3::{Point3, Real},
+    vec3::{Point3, Real, Vec3},
 };
 
 pub struct Sphere<T: Material> {
@@ -24,27 +25,102 @@ impl<T: Material> Sphere<T> {
 }
 
 impl<T: Material> Hitable for Sphere<T> {
-    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord> {
-        let oc = self.center - ray.origin;
-        let a = ray.direction.dot(ray.direction);
-        let b = oc.dot(ray.direction);
-        let c = oc.length_squared() - self.radius * self.radius;
-        let discriminant = b * b - a * c;
-
-        if discriminant > 0.0 {
-            let mut t = (b - discriminant.sqrt()) / a;
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center - r, self.center + r)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        hit_sphere(self.center, self.radius, &self.mat, ray, range)
+    }
+}
+
+/// Intersects the ray with a sphere, returning the nearest hit in `range`.
+fn hit_sphere<'a>(
+    center: Point3,
+    radius: Real,
+    mat: &'a dyn Material,
+    ray: &Ray,
+    range: &Range<Real>,
+) -> Option<HitRecord<'a>> {
+    let oc = center - ray.origin;
+    let a = ray.direction.dot(ray.direction);
+    let b = oc.dot(ray.direction);
+    let c = oc.length_squared() - radius * radius;
+    let discriminant = b * b - a * c;
+
+    if discriminant > 0.0 {
+        let mut t = (b - discriminant.sqrt()) / a;
+        if !range.contains(&t) {
+            t = (b + discriminant.sqrt()) / a;
             if !range.contains(&t) {
-                t = (b + discriminant.sqrt()) / a;
-                if !range.contains(&t) {
-                    return None;
-                }
+                return None;
             }
-            let p = ray.at(t);
-            let normal = (p - self.center).normalize();
+        }
+        let p = ray.at(t);
+        let normal = (p - center).normalize();
+        let (u, v) = uv(normal);
+
+        Some(HitRecord::new(p, normal, t, mat).with_uv(u, v))
+    } else {
+        None
+    }
+}
+
+/// Returns the spherical coordinates of a point on the unit sphere, `u`
+/// going around the y axis starting at -x and `v` from -y up to +y.
+fn uv(p: Vec3) -> (Real, Real) {
+    let theta = (-p.y).clamp(-1.0, 1.0).acos();
+    let phi = (-p.z).atan2(p.x) + PI;
+    (phi / (2.0 * PI), theta / PI)
+}
 
-            Some(HitRecord::new(p, normal, t, &self.mat))
-        } else {
-            None
+/// A sphere moving in a straight line from `center0` at `time0` to
+/// `center1` at `time1`. It stays at the end points outside of that
+/// interval.
+pub struct MovingSphere<T: Material> {
+    center0: Point3,
+    time0: Real,
+    center1: Point3,
+    time1: Real,
+    radius: Real,
+    mat: T,
+}
+
+impl<T: Material> MovingSphere<T> {
+    /// `time1` has to be after `time0`.
+    pub fn new(
+        center0: Point3,
+        time0: Real,
+        center1: Point3,
+        time1: Real,
+        radius: Real,
+        material: T,
+    ) -> Self {
+        MovingSphere {
+            center0,
+            time0,
+            center1,
+            time1,
+            radius,
+            mat: material,
         }
     }
+
+    fn center(&self, time: Real) -> Point3 {
+        let fraction = (time - self.time0) / (self.time1 - self.time0);
+        self.center0 + (self.center1 - self.center0) * fraction.clamp(0.0, 1.0)
+    }
+}
+
+impl<T: Material> Hitable for MovingSphere<T> {
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center0 - r, self.center0 + r)
+            .union(Aabb::new(self.center1 - r, self.center1 + r))
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        hit_sphere(self.center(ray.time), self.radius, &self.mat, ray, range)
+    }
 }
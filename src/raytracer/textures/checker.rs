@@ -0,0 +1,37 @@
+use crate::raytracer::{
+    color::Color,
+    textures::{Texture, solid_color::SolidColor},
+    vec3::{Point3, Real},
+};
+
+/// Solid 3D checker pattern of cubes with sides of length `scale`.
+pub struct CheckerTexture {
+    inv_scale: Real,
+    even: Box<dyn Texture>,
+    odd: Box<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: Real, even: impl Texture + 'static, odd: impl Texture + 'static) -> Self {
+        CheckerTexture {
+            inv_scale: 1.0 / scale,
+            even: Box::new(even),
+            odd: Box::new(odd),
+        }
+    }
+
+    pub fn from_colors(scale: Real, even: Color, odd: Color) -> Self {
+        CheckerTexture::new(scale, SolidColor::new(even), SolidColor::new(odd))
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: Real, v: Real, p: Point3) -> Color {
+        let cell = (p * self.inv_scale).map(Real::floor);
+        if (cell.x + cell.y + cell.z) as i64 % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::raytracer::{
+    color::Color,
+    framebuffer::Framebuffer,
+    textures::Texture,
+    vec3::{Point3, Real},
+};
+
+/// Maps an image onto the surface coordinates, `v` pointing up the image.
+pub struct ImageTexture {
+    image: Framebuffer,
+}
+
+impl ImageTexture {
+    pub fn new(image: Framebuffer) -> Self {
+        ImageTexture { image }
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: Real, v: Real, _p: Point3) -> Color {
+        if self.image.width() == 0 || self.image.height() == 0 {
+            // Cyan stands out as a debugging aid for missing images.
+            return Color::new(0.0, 1.0, 1.0);
+        }
+
+        // Repeat the image outside of the unit square.
+        let u = u.rem_euclid(1.0);
+        let v = 1.0 - v.rem_euclid(1.0);
+        let x = ((u * self.image.width() as Real) as usize).min(self.image.width() - 1);
+        let y = ((v * self.image.height() as Real) as usize).min(self.image.height() - 1);
+        self.image.get(x, y)
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use crate::raytracer::{
+    color::Color,
+    vec3::{Point3, Real},
+};
+
+pub trait Texture: Send + Sync {
+    /// Returns the color at surface coordinates `u`, `v` and hit point `p`.
+    fn value(&self, u: Real, v: Real, p: Point3) -> Color;
+}
+
+/// Lets materials share one texture.
+impl<T: Texture + ?Sized> Texture for Arc<T> {
+    fn value(&self, u: Real, v: Real, p: Point3) -> Color {
+        (**self).value(u, v, p)
+    }
+}
+
+pub mod checker;
+pub mod image;
+pub mod solid_color;
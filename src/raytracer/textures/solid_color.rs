@@ -0,0 +1,21 @@
+use crate::raytracer::{
+    color::Color,
+    textures::Texture,
+    vec3::{Point3, Real},
+};
+
+pub struct SolidColor {
+    albedo: Color,
+}
+
+impl SolidColor {
+    pub fn new(albedo: Color) -> Self {
+        SolidColor { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: Real, _v: Real, _p: Point3) -> Color {
+        self.albedo
+    }
+}
@@ -0,0 +1,127 @@
+use clap::ValueEnum;
+
+use crate::raytracer::{
+    color::Color,
+    vec3::{Real, Vec3},
+};
+
+/// Operators compressing the unbounded radiance of a render into the
+/// displayable range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ToneMapping {
+    /// Cut off everything brighter than white
+    #[default]
+    Clamp,
+    /// L / (1 + L) on the luminance, never reaches white
+    Reinhard,
+    /// Reinhard reaching white at the white point
+    ExtendedReinhard,
+    /// Stephen Hill's fit of the ACES filmic reference and output transforms
+    Aces,
+    /// Troy Sobotka's AgX, desaturates bright colors towards white
+    Agx,
+}
+
+/// Exposure adjustment and tone mapping, applied to linear colors before
+/// they are encoded for display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMapper {
+    pub operator: ToneMapping,
+    /// Exposure adjustment in stops, each one doubles the brightness
+    pub exposure: Real,
+    /// Luminance mapped to white by the extended Reinhard operator
+    pub white_point: Real,
+}
+
+impl Default for ToneMapper {
+    fn default() -> Self {
+        ToneMapper {
+            operator: ToneMapping::Clamp,
+            exposure: 0.0,
+            white_point: 4.0,
+        }
+    }
+}
+
+impl ToneMapper {
+    /// Returns the linear color with every channel in `0..=1`.
+    pub fn apply(&self, color: Color) -> Color {
+        let color = color * self.exposure.exp2();
+        let mapped = match self.operator {
+            ToneMapping::Clamp => color,
+            ToneMapping::Reinhard => scale_luminance(color, |l| l / (1.0 + l)),
+            ToneMapping::ExtendedReinhard => {
+                let white_squared = self.white_point * self.white_point;
+                scale_luminance(color, |l| l * (1.0 + l / white_squared) / (1.0 + l))
+            }
+            ToneMapping::Aces => aces(color),
+            ToneMapping::Agx => agx(color),
+        };
+        Color(mapped.0.map(|c| c.clamp(0.0, 1.0)))
+    }
+}
+
+/// Scales the color so that its luminance becomes `f(luminance)`, keeping
+/// the hue and saturation.
+fn scale_luminance(color: Color, f: impl Fn(Real) -> Real) -> Color {
+    let luminance = color.luminance();
+    if luminance <= 0.0 {
+        return Color::black();
+    }
+    color * (f(luminance) / luminance)
+}
+
+/// Multiplies `v` by the matrix given as rows.
+fn transform(rows: &[[Real; 3]; 3], v: Vec3) -> Vec3 {
+    let row = |r: &[Real; 3]| r[0] * v.x + r[1] * v.y + r[2] * v.z;
+    Vec3::new(row(&rows[0]), row(&rows[1]), row(&rows[2]))
+}
+
+fn aces(color: Color) -> Color {
+    // sRGB to the ACES reference rendering transform input space
+    const INPUT: [[Real; 3]; 3] = [
+        [0.59719, 0.35458, 0.04823],
+        [0.07600, 0.90834, 0.01566],
+        [0.02840, 0.13383, 0.83777],
+    ];
+    // Output device transform space back to sRGB
+    const OUTPUT: [[Real; 3]; 3] = [
+        [1.60475, -0.53108, -0.07367],
+        [-0.10208, 1.10813, -0.00605],
+        [-0.00327, -0.07276, 1.07602],
+    ];
+    let v = transform(&INPUT, color.0).map(|v| {
+        let a = v * (v + 0.0245786) - 0.000090537;
+        let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+        a / b
+    });
+    Color(transform(&OUTPUT, v))
+}
+
+fn agx(color: Color) -> Color {
+    // sRGB to the AgX working space, and back
+    const INSET: [[Real; 3]; 3] = [
+        [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+        [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+        [0.0423756549057051, 0.0784336, 0.879142973793104],
+    ];
+    const OUTSET: [[Real; 3]; 3] = [
+        [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+        [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+        [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+    ];
+    // Range of the log encoding in stops around middle grey
+    const MIN_EV: Real = -12.47393;
+    const MAX_EV: Real = 4.026069;
+
+    let v = transform(&INSET, color.0).map(|v| {
+        let v = (v.max(1e-10).log2().clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV);
+        // Polynomial approximation of the AgX base contrast sigmoid
+        let v2 = v * v;
+        let v4 = v2 * v2;
+        15.5 * v4 * v2 - 40.14 * v4 * v + 31.96 * v4 - 6.868 * v2 * v + 0.4298 * v2 + 0.1191 * v
+            - 0.00232
+    });
+    // The sigmoid produces display encoded values, go back to linear.
+    Color(transform(&OUTSET, v).map(|v| v.max(0.0).powf(2.2)))
+}
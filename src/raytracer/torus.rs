@@ -0,0 +1,272 @@
+use std::{f64::consts::PI, ops::Range};
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    local_frame::{LocalFrame, turn},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Ring of radius `major_radius` around `axis` through `center`, swept by a
+/// circle of radius `minor_radius`. `u` goes around the axis and `v` around
+/// the tube, starting on its inside.
+pub struct Torus<T: Material> {
+    center: Point3,
+    axis: Vec3,
+    major_radius: Real,
+    minor_radius: Real,
+    frame: LocalFrame,
+    mat: T,
+}
+
+impl<T: Material> Torus<T> {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: Real,
+        minor_radius: Real,
+        material: T,
+    ) -> Self {
+        Torus {
+            center,
+            axis: axis.normalize(),
+            major_radius,
+            minor_radius,
+            frame: LocalFrame::along(center, axis),
+            mat: material,
+        }
+    }
+}
+
+impl<T: Material> Hitable for Torus<T> {
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::new(self.minor_radius, self.minor_radius, self.minor_radius);
+        let ring = Aabb::around_disk(self.center, self.axis, self.major_radius);
+        Aabb::new(ring.min - r, ring.max + r)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let local = self.frame.to_local(ray);
+        let length = local.direction.length();
+        let d = local.direction / length;
+
+        // Solve from the point nearest to the center, the polynomial is
+        // badly conditioned far away from the torus. Lengths are measured in
+        // major radii, so the solver's tolerances do not depend on the size
+        // of the torus.
+        let scale = self.major_radius;
+        let shift = -local.origin.dot(d);
+        let o = (local.origin + d * shift) / scale;
+        let minor_radius = self.minor_radius / scale;
+        let outer_radius = 1.0 + minor_radius;
+        if o.length_squared() > outer_radius * outer_radius {
+            return None;
+        }
+
+        // (|p|² + 1 - r²)² = 4 (x² + z²) along p = o + s d
+        let b = o.dot(d);
+        let k = o.length_squared() + 1.0 - minor_radius * minor_radius;
+        let c3 = 4.0 * b;
+        let c2 = 4.0 * b * b + 2.0 * k - 4.0 * (d.x * d.x + d.z * d.z);
+        let c1 = 4.0 * b * k - 8.0 * (o.x * d.x + o.z * d.z);
+        let c0 = k * k - 4.0 * (o.x * o.x + o.z * o.z);
+
+        let t = solve_quartic(c3, c2, c1, c0)
+            .into_iter()
+            .map(|s| (s * scale + shift) / length)
+            .filter(|t| range.contains(t))
+            .min_by(Real::total_cmp)?;
+
+        let p = local.at(t);
+        // The normal points away from the nearest point on the ring.
+        let radial = Vec3::new(p.x, 0.0, p.z);
+        let radial_length = radial.length();
+        let ring = if radial_length > 0.0 {
+            radial * (self.major_radius / radial_length)
+        } else {
+            Vec3::zero()
+        };
+        let normal = (p - ring) / self.minor_radius;
+        let tube_angle = p.y.atan2(radial_length - self.major_radius);
+        let v = (tube_angle + PI) / (2.0 * PI);
+        Some(
+            self.frame
+                .hit_record(ray, t, normal, (turn(p), v), &self.mat),
+        )
+    }
+}
+
+/// Tolerance relative to the size of a polynomial's roots
+const EPSILON: Real = 1e-9;
+
+/// Returns the real roots of `x³ + a x² + b x + c`, using Cardano's method.
+fn solve_cubic(a: Real, b: Real, c: Real) -> Vec<Real> {
+    // Substitute x = y - a/3 to get y³ + 3 p y + 2 q
+    let a2 = a * a;
+    let p = (-a2 / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * a2 - a * b / 3.0 + c) / 2.0;
+    let p3 = p * p * p;
+    let discriminant = q * q + p3;
+
+    // The roots of the depressed cubic are about this large
+    let size = p.abs().sqrt().max(q.abs().cbrt());
+    let roots = if discriminant.abs() <= EPSILON * size.powi(6) {
+        if q.abs() <= EPSILON * size.powi(3) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        // Three real roots
+        let phi = (-q / (-p3).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + PI / 3.0).cos(),
+            -t * (phi - PI / 3.0).cos(),
+        ]
+    } else {
+        let sqrt_d = discriminant.sqrt();
+        vec![(sqrt_d - q).cbrt() - (sqrt_d + q).cbrt()]
+    };
+    roots.into_iter().map(|y| y - a / 3.0).collect()
+}
+
+/// Returns the real roots of `x⁴ + a x³ + b x² + c x + d`, using Ferrari's
+/// method and polishing the roots with Newton's method.
+fn solve_quartic(a: Real, b: Real, c: Real, d: Real) -> Vec<Real> {
+    // Substitute x = y - a/4 to get y⁴ + p y² + q y + r
+    let a2 = a * a;
+    let p = -3.0 / 8.0 * a2 + b;
+    let q = a2 * a / 8.0 - a * b / 2.0 + c;
+    let r = -3.0 / 256.0 * a2 * a2 + a2 * b / 16.0 - a * c / 4.0 + d;
+
+    // The roots of the depressed quartic are about this large
+    let size = p
+        .abs()
+        .sqrt()
+        .max(q.abs().cbrt())
+        .max(r.abs().sqrt().sqrt());
+    let mut roots = Vec::with_capacity(4);
+    if r.abs() <= EPSILON * size.powi(4) {
+        // y (y³ + p y + q) = 0
+        roots.push(0.0);
+        roots.extend(solve_cubic(0.0, p, q));
+    } else {
+        // The largest root of the resolvent cubic splits the quartic into
+        // two real quadratics, smaller ones can make 2 z - p negative.
+        let z = solve_cubic(-p / 2.0, -r, r * p / 2.0 - q * q / 8.0)
+            .into_iter()
+            .fold(Real::NEG_INFINITY, Real::max);
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let sqrt_or_zero = |x: Real, tolerance: Real| {
+            if x.abs() <= tolerance {
+                Some(0.0)
+            } else if x > 0.0 {
+                Some(x.sqrt())
+            } else {
+                None
+            }
+        };
+        let (Some(u), Some(v)) = (
+            sqrt_or_zero(u, EPSILON * size.powi(4)),
+            sqrt_or_zero(v, EPSILON * size.powi(2)),
+        ) else {
+            return roots;
+        };
+        let v = if q < 0.0 { -v } else { v };
+        for (b, c) in [(v, z - u), (-v, z + u)] {
+            let discriminant = b * b / 4.0 - c;
+            if discriminant >= 0.0 {
+                let sqrt_d = discriminant.sqrt();
+                roots.push(-b / 2.0 - sqrt_d);
+                roots.push(-b / 2.0 + sqrt_d);
+            }
+        }
+    }
+
+    let f = |x: Real| (((x + a) * x + b) * x + c) * x + d;
+    let df = |x: Real| ((4.0 * x + 3.0 * a) * x + 2.0 * b) * x + c;
+    roots
+        .into_iter()
+        .map(|y| {
+            let mut x = y - a / 4.0;
+            for _ in 0..2 {
+                // Keep a step only if it improves the root, near double
+                // roots the slope is too flat to trust.
+                let next = x - f(x) / df(x);
+                if f(next).abs() < f(x).abs() {
+                    x = next;
+                }
+            }
+            x
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::{color::Color, materials::lambertian::Lambertian};
+
+    fn torus(major_radius: Real, minor_radius: Real) -> Torus<Lambertian> {
+        let material = Lambertian::new(Color::white());
+        let axis = Vec3::new(0.0, 1.0, 0.0);
+        Torus::new(Point3::zero(), axis, major_radius, minor_radius, material)
+    }
+
+    fn hit_t(torus: &Torus<Lambertian>, origin: Point3, direction: Vec3) -> Option<Real> {
+        let ray = Ray::new(origin, direction);
+        torus.hit(&ray, &(0.0..Real::INFINITY)).map(|hit| hit.t)
+    }
+
+    #[test]
+    fn hits_at_every_scale() {
+        for scale in [0.01, 0.1, 1.0, 100.0, 1e4] {
+            let (major, minor) = (scale, 0.3 * scale);
+            let torus = torus(major, minor);
+            let outer = major + minor;
+            let tolerance = 1e-9 * scale;
+
+            // Along z through the center line, half way to the ring and
+            // through the ring, entering the outer side first.
+            for x in [0.0, 0.5 * major, major] {
+                let origin = Point3::new(x, 0.0, -5.0 * scale);
+                let expected = 5.0 * scale - (outer * outer - x * x).sqrt();
+                let t = hit_t(&torus, origin, Vec3::new(0.0, 0.0, 1.0));
+                assert!(
+                    t.is_some_and(|t| (t - expected).abs() < tolerance),
+                    "scale {scale}, x {x}: expected {expected}, got {t:?}"
+                );
+            }
+
+            // Down through the tube, with a direction that is not a unit
+            // vector
+            let origin = Point3::new(major, 5.0 * scale, 0.0);
+            let expected = (5.0 * scale - minor) / 2.0;
+            let t = hit_t(&torus, origin, Vec3::new(0.0, -2.0, 0.0));
+            assert!(
+                t.is_some_and(|t| (t - expected).abs() < tolerance),
+                "scale {scale}: expected {expected}, got {t:?}"
+            );
+
+            // Just outside the torus and through its hole
+            let origin = Point3::new(1.01 * outer, 0.0, -5.0 * scale);
+            assert_eq!(hit_t(&torus, origin, Vec3::new(0.0, 0.0, 1.0)), None);
+            let origin = Point3::new(0.0, -5.0 * scale, 0.0);
+            assert_eq!(hit_t(&torus, origin, Vec3::new(0.0, 1.0, 0.0)), None);
+        }
+    }
+
+    #[test]
+    fn hits_a_small_torus() {
+        let torus = torus(0.1, 0.03);
+        let origin = Point3::new(0.0, 0.0, -0.5);
+        let t = hit_t(&torus, origin, Vec3::new(0.0, 0.0, 1.0)).unwrap();
+        assert!((t - 0.37).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,79 @@
+use std::ops::Range;
+
+use crate::raytracer::{
+    aabb::Aabb,
+    hitable::{HitRecord, Hitable},
+    materials::Material,
+    ray::Ray,
+    vec3::{Point3, Real, Vec3},
+};
+
+/// Möller–Trumbore ray/triangle intersection. Returns the ray parameter and
+/// the barycentric coordinates of `b` and `c` at the hit point.
+pub fn intersect_triangle(
+    ray: &Ray,
+    a: Point3,
+    b: Point3,
+    c: Point3,
+    range: &Range<Real>,
+) -> Option<(Real, Real, Real)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+    // The ray is parallel to the triangle plane.
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let s = ray.origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inv_det;
+    if !range.contains(&t) {
+        return None;
+    }
+    Some((t, u, v))
+}
+
+/// A single triangle, its outward side is the one from which the vertices
+/// appear counter-clockwise.
+pub struct Triangle<T: Material> {
+    vertices: [Point3; 3],
+    normal: Vec3,
+    mat: T,
+}
+
+impl<T: Material> Triangle<T> {
+    pub fn new(a: Point3, b: Point3, c: Point3, material: T) -> Self {
+        let normal = (b - a).cross(c - a).normalize();
+        Triangle {
+            vertices: [a, b, c],
+            normal,
+            mat: material,
+        }
+    }
+}
+
+impl<T: Material> Hitable for Triangle<T> {
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.vertices;
+        Aabb::from_points(a, b).grow(c)
+    }
+
+    fn hit(&self, ray: &Ray, range: &Range<Real>) -> Option<HitRecord<'_>> {
+        let [a, b, c] = self.vertices;
+        let (t, u, v) = intersect_triangle(ray, a, b, c, range)?;
+        Some(HitRecord::new(ray.at(t), self.normal, t, &self.mat).with_uv(u, v))
+    }
+}
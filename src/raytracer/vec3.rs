@@ -4,14 +4,18 @@ use std::{
 };
 
 use derive_more::{Add, AddAssign, Display, Mul, Neg, Sub, SubAssign};
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+use serde::Deserialize;
 
 use crate::raytracer::color::Color;
 
 pub type Real = f64;
 
-#[derive(Debug, Clone, Copy, PartialEq, Add, AddAssign, Sub, SubAssign, Mul, Display, Neg)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Add, AddAssign, Sub, SubAssign, Mul, Display, Neg, Deserialize,
+)]
 #[display("({}, {}, {})", x, y, z)]
+#[serde(from = "[Real; 3]")]
 pub struct Vec3 {
     pub x: Real,
     pub y: Real,
@@ -19,7 +23,28 @@ pub struct Vec3 {
 }
 
 thread_local! {
-    static RNG: UnsafeCell<rand::rngs::ThreadRng> = UnsafeCell::new(rand::rng());
+    static RNG: UnsafeCell<SmallRng> = UnsafeCell::new(SmallRng::from_rng(&mut rand::rng()));
+}
+
+/// Restarts the current thread's random number stream from `seed`, making
+/// everything sampled afterwards on this thread reproducible.
+pub fn seed_rng(seed: u64) {
+    RNG.with(|rng| {
+        // Safety: we only have one &mut to the RNG at a time.
+        let rng = unsafe { &mut *rng.get() };
+        *rng = SmallRng::seed_from_u64(seed);
+    })
+}
+
+/// SplitMix64's increment, 2^64 divided by the golden ratio. Adding
+/// multiples of it spreads consecutive indices apart before `mix64`.
+pub const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// SplitMix64's finalizer, hashes the value into 64 well mixed bits.
+pub fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
 /// For clarity
@@ -59,6 +84,37 @@ impl Vec3 {
         )
     }
 
+    /// Returns two unit vectors perpendicular to this unit vector and to
+    /// each other
+    pub fn orthonormal_basis(self) -> (Vec3, Vec3) {
+        // Duff et al., "Building an Orthonormal Basis, Revisited"
+        let sign = Real::copysign(1.0, self.z);
+        let a = -1.0 / (sign + self.z);
+        let b = self.x * self.y * a;
+        (
+            Vec3::new(1.0 + sign * self.x * self.x * a, sign * b, -sign * self.x),
+            Vec3::new(b, sign + self.y * self.y * a, -self.y),
+        )
+    }
+
+    /// Component-wise minimum of two vectors
+    pub fn min(self, other: Vec3) -> Self {
+        Vec3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Component-wise maximum of two vectors
+    pub fn max(self, other: Vec3) -> Self {
+        Vec3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
     pub fn map(self, f: fn(Real) -> Real) -> Self {
         Vec3::new(f(self.x), f(self.y), f(self.z))
     }
@@ -96,6 +152,15 @@ impl Vec3 {
         })
     }
 
+    /// Returns a random number in `0..1`.
+    pub fn random_real() -> Real {
+        RNG.with(|rng| {
+            // Safety: we only have one &mut to the RNG at a time.
+            let rng = unsafe { &mut *rng.get() };
+            rng.random::<Real>()
+        })
+    }
+
     pub fn random(interval: Range<Real>) -> Self {
         RNG.with(|rng| {
             // Safety: we only have one &mut to the RNG at a time.
@@ -157,3 +222,22 @@ impl ops::Div<Real> for Vec3 {
         Vec3::new(self.x / scalar, self.y / scalar, self.z / scalar)
     }
 }
+
+impl From<[Real; 3]> for Vec3 {
+    fn from([x, y, z]: [Real; 3]) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl ops::Index<usize> for Vec3 {
+    type Output = Real;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vec3 index out of range: {}", axis),
+        }
+    }
+}